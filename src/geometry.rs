@@ -0,0 +1,458 @@
+use std::cmp::Ordering;
+
+use cgmath::{Matrix3, Vector2};
+use cgmath::num_traits::FloatConst;
+use cgmath::prelude::*;
+
+use crate::Orientation::*;
+
+pub type Point = Vector2<f64>;
+pub type Mat = Matrix3<f64>;
+
+/// Tolerance for point comparisons relative to the size of the input, see [`Aabb::epsilon`].
+pub const RELATIVE_EPSILON: f64 = 1e-9;
+
+/// Homogeneous transform moving points by `(dx, dy)`.
+pub fn translation(dx: f64, dy: f64) -> Mat {
+    // cgmath takes the entries column by column, so every line here is one column
+    Mat::new(
+        1.0, 0.0, 0.0,
+        0.0, 1.0, 0.0,
+        dx, dy, 1.0,
+    )
+}
+
+/// Homogeneous transform rotating points ccw around the origin.
+pub fn rotation(radians: f64) -> Mat {
+    let (sin, cos) = radians.sin_cos();
+    Mat::new(
+        cos, sin, 0.0,
+        -sin, cos, 0.0,
+        0.0, 0.0, 1.0,
+    )
+}
+
+/// Homogeneous transform scaling points relative to the origin, a negative factor mirrors.
+pub fn scaling(sx: f64, sy: f64) -> Mat {
+    Mat::new(
+        sx, 0.0, 0.0,
+        0.0, sy, 0.0,
+        0.0, 0.0, 1.0,
+    )
+}
+
+/// Homogeneous transform mirroring points across the line through the origin along `direction`,
+/// which has to be non-zero but doesn't have to be a unit vector.
+pub fn reflection(direction: Point) -> Mat {
+    let d = direction.normalize();
+    let (cos, sin) = (d.x * d.x - d.y * d.y, 2.0 * d.x * d.y);
+    Mat::new(
+        cos, sin, 0.0,
+        sin, -cos, 0.0,
+        0.0, 0.0, 1.0,
+    )
+}
+
+pub fn angle(p: &Point) -> f64 {
+    let a = f64::atan2(p.y, p.x);
+    if a < 0.0 {
+        return a + 2.0 * f64::PI();
+    }
+    a
+}
+
+pub fn wrapped_angle_sub(angle: f64, sub: f64) -> f64 {
+    let s = angle - sub;
+    if s < 0.0 {
+        return s + 2.0 * f64::PI();
+    }
+    s
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum Orientation {
+    Leftwards,
+    Collinear,
+    Rightwards,
+}
+
+/// The determinant of `[[1, 1, 1], [s.x, p.x, e.x], [s.y, p.y, e.y]]`, twice the signed area of
+/// the triangle `s p e`. Positive if `s p e` are counter-clockwise which [`Orientation::calc`] calls
+/// `Leftwards`, negative for clockwise and zero if collinear.
+pub fn orient2d(s: &Point, p: &Point, e: &Point) -> f64 {
+    // expanded relative to s, unlike the full 3x3 determinant this is exactly zero whenever two of
+    // the points coincide
+    (p.x - s.x) * (e.y - s.y) - (e.x - s.x) * (p.y - s.y)
+}
+
+impl Orientation {
+    pub fn calc(s: &Point, p: &Point, e: &Point) -> Orientation {
+        let sign = orient2d(s, p, e);
+
+        if sign < 0.0 {
+            return Rightwards;
+        }
+
+        if sign > 0.0 {
+            return Leftwards;
+        }
+
+        Collinear
+    }
+
+    /// The orientation of the same points in reverse order, `Collinear` stays the same.
+    pub fn flip(self) -> Orientation {
+        match self {
+            Leftwards => Rightwards,
+            Collinear => Collinear,
+            Rightwards => Leftwards,
+        }
+    }
+}
+
+/// Whether `a` and `b` are at most `eps` apart, with `eps = 0` this is exact equality.
+pub fn points_approx_eq(a: &Point, b: &Point, eps: f64) -> bool {
+    (a - b).magnitude() <= eps
+}
+
+/// The default tolerance for comparing `points` with each other, [`Aabb::epsilon`] of their
+/// bounding box. It grows with the spread of the points so it works the same whether they are in
+/// the unit square or spread over millions, no points give 0.
+pub fn suggested_epsilon(points: &[Point]) -> f64 {
+    Aabb::from_points(points.iter().copied()).map_or(0.0, |bbox| bbox.epsilon())
+}
+
+/// Lexicographic order by x and then y which is total, so sorting never depends on the input order.
+/// `-0.0` and `0.0` compare equal like they do with `==`, only `total_cmp` alone would separate
+/// them, and NaN sorts after every number whatever its sign bit.
+pub fn cmp_points_xy(a: &Point, b: &Point) -> Ordering {
+    // adding zero turns -0.0 into 0.0 and leaves everything else as it is, `total_cmp` would put a
+    // NaN with the sign bit set before every number so all of them become the positive one
+    let normalize = |v: f64| if v.is_nan() { f64::NAN } else { v + 0.0 };
+    let key = |p: &Point| (normalize(p.x), normalize(p.y));
+    let (a, b) = (key(a), key(b));
+    a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+}
+
+/// Drops every point which is within `eps` of an earlier one, the remaining points keep their
+/// order. Sorting by x first means each point is only compared with its neighbours in x.
+pub fn dedup_points(points: Vec<Point>, eps: f64) -> Vec<Point> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| cmp_points_xy(&points[a], &points[b]).then(a.cmp(&b)));
+
+    // kept indices sorted by x, a point can only be close to the ones at the end
+    let mut kept: Vec<usize> = vec![];
+    for i in order {
+        let p = &points[i];
+        let duplicate = kept.iter()
+            .rev()
+            .take_while(|&&k| points[k].x >= p.x - eps)
+            .any(|&k| points_approx_eq(&points[k], p, eps));
+
+        if !duplicate {
+            kept.push(i);
+        }
+    }
+
+    kept.sort();
+    kept.into_iter().map(|i| points[i]).collect()
+}
+
+/// Distance from `p` to the closest point of the segment `a b`, which is just a point if `a == b`.
+pub fn point_segment_distance(p: &Point, a: &Point, b: &Point) -> f64 {
+    (p - closest_point_on_segment(p, a, b)).magnitude()
+}
+
+/// The point of the segment `a b` which is the closest to `p`.
+pub fn closest_point_on_segment(p: &Point, a: &Point, b: &Point) -> Point {
+    let ab = b - a;
+    let length2 = ab.magnitude2();
+    if length2 == 0.0 {
+        return *a;
+    }
+
+    let t = ((p - a).dot(ab) / length2).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+// the part of the polygon where `side` is at least 0, one step of the sutherland hodgman clipping,
+// `side` has to be affine like the signed distance to a line
+pub(crate) fn clip_half_plane(polygon: &[Point], side: impl Fn(&Point) -> f64) -> Vec<Point> {
+    let n = polygon.len();
+    (0..n)
+        .flat_map(|k| {
+            let (a, b) = (polygon[k], polygon[(k + 1) % n]);
+            let (fa, fb) = (side(&a), side(&b));
+            let keep = (fa >= 0.0).then_some(a);
+            let cross = (fa * fb < 0.0).then(|| a + (b - a) * (fa / (fa - fb)));
+            keep.into_iter().chain(cross)
+        })
+        .collect()
+}
+
+// whether p lies within the bounding box of segment s e, only meaningful if all three are collinear
+fn on_segment(s: &Point, p: &Point, e: &Point) -> bool {
+    p.x >= s.x.min(e.x) && p.x <= s.x.max(e.x) && p.y >= s.y.min(e.y) && p.y <= s.y.max(e.y)
+}
+
+/// Whether the closed segments `a1 a2` and `b1 b2` intersect, touching endpoints count.
+pub fn segment_intersects(a1: &Point, a2: &Point, b1: &Point, b2: &Point) -> bool {
+    let o1 = Orientation::calc(a1, b1, a2);
+    let o2 = Orientation::calc(a1, b2, a2);
+    let o3 = Orientation::calc(b1, a1, b2);
+    let o4 = Orientation::calc(b1, a2, b2);
+
+    if o1 != o2 && o3 != o4 {
+        return true;
+    }
+
+    (o1 == Collinear && on_segment(a1, b1, a2)) ||
+        (o2 == Collinear && on_segment(a1, b2, a2)) ||
+        (o3 == Collinear && on_segment(b1, a1, b2)) ||
+        (o4 == Collinear && on_segment(b1, a2, b2))
+}
+
+/// Intersection point of the closed segments `a1 a2` and `b1 b2`. If the segments are collinear
+/// and overlap there is no single intersection point, then the endpoint of the overlap which
+/// is the closest to `a1` is returned.
+pub fn segment_intersection(a1: Point, a2: Point, b1: Point, b2: Point) -> Option<Point> {
+    if !segment_intersects(&a1, &a2, &b1, &b2) {
+        return None;
+    }
+
+    let r = a2 - a1;
+    let s = b2 - b1;
+    let denom = r.perp_dot(s);
+
+    if denom == 0.0 {
+        // parallel segments only intersect here if they are collinear and overlap
+        if on_segment(&b1, &a1, &b2) {
+            return Some(a1);
+        }
+
+        return [b1, b2].into_iter()
+            .filter(|p| on_segment(&a1, p, &a2))
+            .min_by(|p, q| (p - a1).magnitude2().total_cmp(&(q - a1).magnitude2()));
+    }
+
+    // the orientation test already guarantees t is in [0, 1] up to rounding
+    let t = (b1 - a1).perp_dot(s) / denom;
+    Some(a1 + r * t.clamp(0.0, 1.0))
+}
+
+/// Axis-aligned bounding box, both `min` and `max` are inclusive.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Aabb {
+    /// The smallest box containing all `points`, none if there are no points.
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Option<Aabb> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+
+        Some(points.fold(Aabb { min: first, max: first }, |bbox, p| Aabb {
+            min: Point::new(bbox.min.x.min(p.x), bbox.min.y.min(p.y)),
+            max: Point::new(bbox.max.x.max(p.x), bbox.max.y.max(p.y)),
+        }))
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// Boxes which only touch along an edge or in a corner also intersect.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x &&
+            self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    /// The default tolerance for comparing points within this box, [`RELATIVE_EPSILON`] times
+    /// the length of the diagonal. This keeps the comparison meaningful regardless of whether
+    /// the coordinates are in the order of 1e-3 or 1e6.
+    pub fn epsilon(&self) -> f64 {
+        RELATIVE_EPSILON * (self.max - self.min).magnitude()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aabb(x0: f64, y0: f64, x1: f64, y1: f64) -> Aabb {
+        Aabb { min: Point::new(x0, y0), max: Point::new(x1, y1) }
+    }
+
+    #[test]
+    fn aabb_contains_is_inclusive() {
+        let bbox = aabb(0.0, 0.0, 2.0, 1.0);
+
+        assert!(bbox.contains(&Point::new(1.0, 0.5)));
+        assert!(bbox.contains(&Point::new(0.0, 0.0)));
+        assert!(bbox.contains(&Point::new(2.0, 0.5)));
+        assert!(!bbox.contains(&Point::new(2.1, 0.5)));
+        assert!(!bbox.contains(&Point::new(1.0, -0.1)));
+    }
+
+    #[test]
+    fn aabb_intersects_touching_boxes() {
+        let bbox = aabb(0.0, 0.0, 2.0, 2.0);
+
+        assert!(bbox.intersects(&aabb(1.0, 1.0, 3.0, 3.0)));
+        assert!(bbox.intersects(&aabb(2.0, 0.5, 3.0, 1.5)));
+        assert!(bbox.intersects(&aabb(2.0, 2.0, 3.0, 3.0)));
+        assert!(bbox.intersects(&aabb(0.5, 0.5, 1.0, 1.0)));
+        assert!(!bbox.intersects(&aabb(2.1, 0.0, 3.0, 2.0)));
+        assert!(!bbox.intersects(&aabb(0.0, -2.0, 2.0, -0.1)));
+    }
+
+    #[test]
+    fn segment_intersection_cases() {
+        let p = Point::new;
+
+        // crossing
+        assert_eq!(segment_intersection(p(0.0, 0.0), p(2.0, 2.0), p(0.0, 2.0), p(2.0, 0.0)), Some(p(1.0, 1.0)));
+        // touching in an endpoint
+        assert_eq!(segment_intersection(p(0.0, 0.0), p(1.0, 0.0), p(1.0, 0.0), p(1.0, 3.0)), Some(p(1.0, 0.0)));
+        // parallel and disjoint
+        assert_eq!(segment_intersection(p(0.0, 0.0), p(2.0, 0.0), p(0.0, 1.0), p(2.0, 1.0)), None);
+        assert!(!segment_intersects(&p(0.0, 0.0), &p(2.0, 0.0), &p(0.0, 1.0), &p(2.0, 1.0)));
+        // collinear and overlapping, the end of the overlap closest to the start of the first segment
+        assert_eq!(segment_intersection(p(0.0, 0.0), p(4.0, 0.0), p(3.0, 0.0), p(1.0, 0.0)), Some(p(1.0, 0.0)));
+        assert_eq!(segment_intersection(p(2.0, 0.0), p(4.0, 0.0), p(0.0, 0.0), p(3.0, 0.0)), Some(p(2.0, 0.0)));
+    }
+
+    #[test]
+    fn points_approx_eq_with_default_and_zero_eps() {
+        let points = [Point::new(0.0, 0.0), Point::new(10.0, 10.0)];
+        let a = Point::new(3.0, 4.0);
+        let b = Point::new(3.0 + 1e-12, 4.0);
+
+        assert!(points_approx_eq(&a, &b, suggested_epsilon(&points)));
+        assert!(!points_approx_eq(&a, &b, 0.0));
+        assert!(points_approx_eq(&a, &a, 0.0));
+    }
+
+    #[test]
+    fn orient2d_sign_and_magnitude() {
+        let (s, p) = (Point::new(0.0, 0.0), Point::new(2.0, 0.0));
+        let cases = [
+            (Point::new(1.0, 3.0), Orientation::Leftwards),
+            (Point::new(1.0, -3.0), Orientation::Rightwards),
+            (Point::new(5.0, 0.0), Orientation::Collinear),
+        ];
+
+        for (e, orientation) in cases {
+            let det = orient2d(&s, &p, &e);
+            assert_eq!(Orientation::calc(&s, &p, &e), orientation);
+            assert_eq!(det > 0.0, orientation == Orientation::Leftwards);
+            assert_eq!(det < 0.0, orientation == Orientation::Rightwards);
+        }
+
+        // twice the triangle area, scaling all points by 3 scales the area by 9
+        assert_eq!(orient2d(&s, &p, &Point::new(1.0, 3.0)), 6.0);
+        assert_eq!(orient2d(&(s * 3.0), &(p * 3.0), &Point::new(3.0, 9.0)), 54.0);
+    }
+
+    fn apply(m: &Mat, p: Point) -> Point {
+        let v = m * cgmath::Vector3::new(p.x, p.y, 1.0);
+        Point::new(v.x, v.y)
+    }
+
+    #[test]
+    fn matrix_constructors_on_a_known_point() {
+        let p = Point::new(2.0, 1.0);
+        let close = |a: Point, b: Point| (a - b).magnitude() < 1e-12;
+
+        assert_eq!(apply(&translation(3.0, -4.0), p), Point::new(5.0, -3.0));
+        assert!(close(apply(&rotation(f64::FRAC_PI_2()), p), Point::new(-1.0, 2.0)));
+        assert_eq!(apply(&scaling(2.0, -3.0), p), Point::new(4.0, -3.0));
+        assert!(close(apply(&reflection(Point::new(1.0, 0.0)), p), Point::new(2.0, -1.0)));
+        assert!(close(apply(&reflection(Point::new(3.0, 3.0)), p), Point::new(1.0, 2.0)));
+    }
+
+    #[test]
+    fn point_segment_distance_cases() {
+        let (a, b) = (Point::new(0.0, 0.0), Point::new(4.0, 0.0));
+
+        // beyond an endpoint the distance is to that endpoint, beside the segment it is perpendicular
+        assert_eq!(point_segment_distance(&Point::new(7.0, 4.0), &a, &b), 5.0);
+        assert_eq!(point_segment_distance(&Point::new(-3.0, 0.0), &a, &b), 3.0);
+        assert_eq!(point_segment_distance(&Point::new(2.0, -1.5), &a, &b), 1.5);
+        assert_eq!(point_segment_distance(&Point::new(1.0, 0.0), &a, &b), 0.0);
+        assert_eq!(point_segment_distance(&Point::new(3.0, 4.0), &a, &a), 5.0);
+    }
+
+    #[test]
+    fn flip_matches_reversed_points() {
+        assert_eq!(Leftwards.flip(), Rightwards);
+        assert_eq!(Rightwards.flip(), Leftwards);
+        assert_eq!(Collinear.flip(), Collinear);
+
+        let (s, e) = (Point::new(0.0, 0.0), Point::new(2.0, 1.0));
+        for p in [Point::new(0.0, 3.0), Point::new(3.0, 0.0), Point::new(4.0, 2.0)] {
+            assert_eq!(Orientation::calc(&s, &p, &e).flip(), Orientation::calc(&e, &p, &s));
+        }
+    }
+
+    #[test]
+    fn aabb_from_points() {
+        assert_eq!(Aabb::from_points([]), None);
+        assert_eq!(Aabb::from_points([Point::new(1.0, 2.0)]), Some(aabb(1.0, 2.0, 1.0, 2.0)));
+        assert_eq!(
+            Aabb::from_points([Point::new(3.0, -1.0), Point::new(-2.0, 4.0), Point::new(0.0, 0.0)]),
+            Some(aabb(-2.0, -1.0, 3.0, 4.0)),
+        );
+    }
+
+    #[test]
+    fn suggested_epsilon_scales_with_the_input() {
+        let points = [Point::new(0.0, 0.0), Point::new(0.3, 0.4), Point::new(0.1, 0.2)];
+        let base = suggested_epsilon(&points);
+        assert!((base - RELATIVE_EPSILON * 0.5).abs() < 1e-24);
+
+        for scale in [1e-3, 1e3, 1e6] {
+            let scaled: Vec<Point> = points.iter().map(|p| p * scale).collect();
+            let eps = suggested_epsilon(&scaled);
+            assert!((eps / base - scale).abs() < scale * 1e-12, "{scale}");
+        }
+
+        // moving the points doesn't change their spread
+        let moved: Vec<Point> = points.iter().map(|p| p + Point::new(1e3, -1e3)).collect();
+        assert!((suggested_epsilon(&moved) - base).abs() < base * 1e-6);
+        assert_eq!(suggested_epsilon(&[]), 0.0);
+    }
+
+    #[test]
+    fn cmp_points_xy_sorts_totally_and_stably() {
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+
+        let p = Point::new;
+        let input = [
+            p(1.0, 2.0), p(0.0, 5.0), p(1.0, 2.0), p(-0.0, 1.0), p(0.0, 1.0), p(1.0, -1.0),
+            p(f64::NAN, 0.0), p(-f64::NAN, 0.0), p(f64::NEG_INFINITY, 0.0),
+        ];
+
+        // equal points keep their input order, -0.0 and 0.0 count as equal and NaN goes last
+        // whatever its sign
+        let mut order: Vec<usize> = (0..input.len()).collect();
+        order.sort_by(|&a, &b| cmp_points_xy(&input[a], &input[b]));
+        assert_eq!(order, vec![8, 3, 4, 1, 5, 0, 2, 6, 7]);
+
+        // every input order sorts into the same sequence
+        let finite = &input[..6];
+        let mut expected = finite.to_vec();
+        expected.sort_by(cmp_points_xy);
+        let mut rng = StdRng::seed_from_u64(388);
+        for _ in 0..20 {
+            let mut shuffled = finite.to_vec();
+            shuffled.shuffle(&mut rng);
+            shuffled.sort_by(cmp_points_xy);
+            assert_eq!(shuffled, expected);
+        }
+    }
+}
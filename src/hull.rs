@@ -0,0 +1,3110 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+use cgmath::{InnerSpace, Vector3};
+use cgmath::num_traits::FloatConst;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+
+use crate::{Aabb, angle, clip_half_plane, cmp_points_xy, dedup_points, Mat, orient2d, Orientation, Point, point_segment_distance, points_approx_eq, reflection, scaling, suggested_epsilon, translation, wrapped_angle_sub};
+use crate::Orientation::*;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HullError {
+    Empty,
+    // `index` is the position of the first point with a nan or infinite coordinate
+    NonFinite { index: usize },
+    // `index` is the first vertex where a supposed hull turns cw, repeats a point or winds around again
+    NotConvex { index: usize },
+}
+
+impl fmt::Display for HullError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HullError::Empty => write!(f, "a hull needs at least one point"),
+            HullError::NonFinite { index } => write!(f, "point {index} has a non-finite coordinate"),
+            HullError::NotConvex { index } => write!(f, "vertex {index} breaks the convexity of the ccw hull"),
+        }
+    }
+}
+
+impl Error for HullError {}
+
+/// What the graham scan of [`ConvexPoly::new_with_stats`] did. `comparisons` counts the
+/// orientation tests, the sorting before the scan isn't included.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct HullStats {
+    pub pops: usize,
+    pub pushes: usize,
+    pub comparisons: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConvexPoly {
+    // every input point, for polygons built by `new` not in input order but sorted ccw around the
+    // lowest point which comes first
+    all: Vec<Point>,
+    // ccw and always starts at the lowest vertex, the leftmost one of them if there are several
+    hull: Vec<Point>,
+    // ids of the hull vertices for polygons built by `new_with_ids`, empty otherwise
+    hull_ids: Vec<u64>,
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+}
+
+impl ConvexPoly {
+    /// Computes the convex hull of `points`, fails if there are none or if any coordinate is nan
+    /// or infinite as those can't be ordered meaningfully.
+    pub fn new(points: Vec<Point>) -> Result<Self, HullError> {
+        validate(points.iter())?;
+        Ok(Self::from_valid(points))
+    }
+
+    /// Same as [`ConvexPoly::new`] but every point carries an id, the ids of the points which end
+    /// up as hull vertices are available through [`ConvexPoly::hull_ids`].
+    pub fn new_with_ids(mut points: Vec<(Point, u64)>) -> Result<Self, HullError> {
+        validate(points.iter().map(|(p, _)| p))?;
+
+        let hull = graham_scan_by(&mut points, |(p, _)| *p, |_| {});
+        let mut poly = Self::from_hull(
+            points.iter().map(|(p, _)| *p).collect(),
+            hull.iter().map(|(p, _)| *p).collect(),
+        );
+        poly.hull_ids = hull.iter().map(|(_, id)| *id).collect();
+
+        Ok(poly)
+    }
+
+    /// Same as [`ConvexPoly::new`] but also counts the stack operations of the graham scan.
+    pub fn new_with_stats(mut points: Vec<Point>) -> Result<(Self, HullStats), HullError> {
+        validate(points.iter())?;
+
+        let mut stats = HullStats::default();
+        let mut len = 0;
+        // the scan reports the stack after every push and pop, every pop and every push onto
+        // a stack of at least 2 points follows one orientation test
+        let hull = graham_scan(&mut points, |stack| {
+            if stack.len() > len {
+                stats.pushes += 1;
+                if stack.len() > 2 {
+                    stats.comparisons += 1;
+                }
+            } else {
+                stats.pops += 1;
+                stats.comparisons += 1;
+            }
+            len = stack.len();
+        });
+
+        Ok((Self::from_hull(points, hull), stats))
+    }
+
+    /// Takes `hull` as the hull without running the graham scan, it has to be a non-empty convex
+    /// polygon in ccw order. Only the start is rotated to the lowest vertex as the queries expect,
+    /// `all` is the hull itself. Use [`ConvexPoly::from_hull_checked`] for untrusted input.
+    pub fn from_hull_unchecked(mut hull: Vec<Point>) -> ConvexPoly {
+        rotate_to_lowest(&mut hull);
+        Self::from_hull(hull.clone(), hull)
+    }
+
+    /// Same as [`ConvexPoly::from_hull_unchecked`] but fails if `hull` is empty, has non-finite
+    /// coordinates or isn't convex and ccw. Collinear vertices are fine, repeated ones are not.
+    pub fn from_hull_checked(hull: Vec<Point>) -> Result<ConvexPoly, HullError> {
+        validate(hull.iter())?;
+
+        if let Some(index) = first_non_convex(&hull, Leftwards) {
+            return Err(HullError::NotConvex { index });
+        }
+
+        Ok(Self::from_hull_unchecked(hull))
+    }
+
+    /// Same hull as [`ConvexPoly::new`] but [`akl_toussaint_filter`] drops most interior points
+    /// before the scan, which pays off for large inputs. Unlike with `new` the points in `all`
+    /// stay in input order.
+    pub fn new_fast(points: Vec<Point>) -> Result<Self, HullError> {
+        validate(points.iter())?;
+
+        let hull = graham_scan(&mut akl_toussaint_filter(&points), |_| {});
+        Ok(Self::from_hull(points, hull))
+    }
+
+    pub fn builder() -> ConvexPolyBuilder {
+        ConvexPolyBuilder::default()
+    }
+
+    // `new` without the validation, for point sets which are known to be non-empty and finite
+    fn from_valid(mut points: Vec<Point>) -> Self {
+        let hull = graham_scan(&mut points, |_| {});
+        Self::from_hull(points, hull)
+    }
+
+    fn from_hull(all: Vec<Point>, hull: Vec<Point>) -> Self {
+        // the hull is ccw and starts at the lowest point, no rotation is needed as the
+        // fast query measures all angles relative to whichever vertex comes first
+
+        // TODO: find min max during hull construction or sorting
+        let bbox = Aabb::from_points(all.iter().copied()).unwrap();
+        ConvexPoly {
+            all,
+            hull,
+            hull_ids: vec![],
+            x_min: bbox.min.x,
+            x_max: bbox.max.x,
+            y_min: bbox.min.y,
+            y_max: bbox.max.y,
+        }
+    }
+
+    /// The hull vertices in ccw order as an open ring, the edge back from the last to the first
+    /// vertex is implied. See [`ConvexPoly::hull_closed`] for formats which want it spelled out.
+    pub fn hull(&self) -> &[Point] {
+        &self.hull
+    }
+
+    /// Same as [`ConvexPoly::hull`] but with the first vertex repeated at the end, so the ring is
+    /// explicitly closed.
+    pub fn hull_closed(&self) -> Vec<Point> {
+        let mut closed = self.hull.clone();
+        closed.push(self.hull[0]);
+        closed
+    }
+
+    pub fn all(&self) -> &[Point] {
+        &self.all
+    }
+
+    /// Ids of the hull vertices in the same order as [`ConvexPoly::hull`]. Only polygons built by
+    /// [`ConvexPoly::new_with_ids`] have ids, anything which changes the hull afterwards drops them.
+    pub fn hull_ids(&self) -> Vec<u64> {
+        self.hull_ids.clone()
+    }
+
+    pub fn num_vertices(&self) -> usize {
+        self.hull.len()
+    }
+
+    /// The hull vertices in ccw order starting at the smallest one by x and then y, which unlike
+    /// the stored start doesn't depend on how the hull was built.
+    pub fn vertices_ccw(&self) -> impl Iterator<Item = Point> + '_ {
+        let n = self.hull.len();
+        let start = self.lexicographic_start();
+        (0..n).map(move |k| self.hull[(start + k) % n])
+    }
+
+    /// Same as [`ConvexPoly::vertices_ccw`] but in cw order, both start at the same vertex.
+    pub fn vertices_cw(&self) -> impl Iterator<Item = Point> + '_ {
+        let n = self.hull.len();
+        let start = self.lexicographic_start();
+        (0..n).map(move |k| self.hull[(start + n - k) % n])
+    }
+
+    /// The corners of the hull as in [`ConvexPoly::vertices_ccw`], vertices in the middle of an edge
+    /// are left out. A degenerate hull gives the two ends of its segment or its single point. Hulls
+    /// of the same shape have the same canonical form no matter how they were built.
+    pub fn canonical(&self) -> Vec<Point> {
+        if self.is_degenerate() {
+            let mut ends = vec![
+                *self.hull.iter().min_by(|a, b| cmp_points_xy(a, b)).unwrap(),
+                *self.hull.iter().max_by(|a, b| cmp_points_xy(a, b)).unwrap(),
+            ];
+            ends.dedup();
+            return ends;
+        }
+
+        let n = self.hull.len();
+        let start = self.lexicographic_start();
+        (0..n)
+            .map(|k| (start + k) % n)
+            .filter(|&i| is_corner(&self.hull, i))
+            .map(|i| self.hull[i])
+            .collect()
+    }
+
+    // index of the smallest hull vertex by `cmp_points_xy`
+    fn lexicographic_start(&self) -> usize {
+        (0..self.hull.len())
+            .min_by(|&a, &b| cmp_points_xy(&self.hull[a], &self.hull[b]))
+            .unwrap()
+    }
+
+    /// Whether the hull encloses no area, i.e. it is a single point or all of its vertices are
+    /// collinear. Most queries which need an inside, like the fast containment test, can't
+    /// handle such hulls.
+    pub fn is_degenerate(&self) -> bool {
+        let n = self.hull.len();
+        !(0..n).any(|i| is_corner(&self.hull, i))
+    }
+
+    /// The hull vertices as plain `(x, y)` tuples, for callers which don't use cgmath.
+    pub fn hull_coords(&self) -> Vec<(f64, f64)> {
+        self.hull.iter().map(|p| (p.x, p.y)).collect()
+    }
+
+    /// All points as plain `(x, y)` tuples, in the same order as [`ConvexPoly::all`].
+    pub fn all_coords(&self) -> Vec<(f64, f64)> {
+        self.all.iter().map(|p| (p.x, p.y)).collect()
+    }
+
+    /// The hull as the `d` attribute of an svg path, with `flip_height` y is mapped to
+    /// `height - y` like the renderer does when flipping the y axis.
+    pub fn to_svg_path(&self, flip_height: Option<f64>) -> String {
+        let coords = |p: &Point| match flip_height {
+            Some(height) => format!("{},{}", p.x, height - p.y),
+            None => format!("{},{}", p.x, p.y),
+        };
+
+        let mut d = format!("M {}", coords(&self.hull[0]));
+        for p in &self.hull {
+            d += &format!(" L {}", coords(p));
+        }
+        d += " Z";
+        d
+    }
+
+    pub fn bounding_box(&self) -> Aabb {
+        Aabb {
+            min: Point::new(self.x_min, self.y_min),
+            max: Point::new(self.x_max, self.y_max),
+        }
+    }
+
+    /// Splits the hull into `hull.len() - 2` triangles which all share the first hull vertex.
+    pub fn triangulate_fan(&self) -> Vec<[Point; 3]> {
+        self.fan_triangles().collect()
+    }
+
+    /// The triangles of [`ConvexPoly::triangulate_fan`] one by one without collecting them.
+    pub fn fan_triangles(&self) -> impl Iterator<Item = [Point; 3]> + '_ {
+        (1..self.hull.len().saturating_sub(1))
+            .map(|i| [self.hull[0], self.hull[i], self.hull[i + 1]])
+    }
+
+    /// How many of `points` lie in each triangle of [`ConvexPoly::triangulate_fan`], in the same
+    /// order. A point on a diagonal shared by two triangles is counted for the first of them and
+    /// points outside of the hull aren't counted at all.
+    pub fn bin_points_by_fan_triangle(&self, points: &[Point]) -> Vec<usize> {
+        let triangles = self.triangulate_fan();
+        let mut counts = vec![0; triangles.len()];
+
+        for p in points {
+            let contains = |[a, b, c]: &[Point; 3]| [(a, b), (b, c), (c, a)]
+                .into_iter()
+                .all(|(s, e)| Inclusion::Inclusive.accepts(Orientation::calc(s, p, e)));
+
+            if let Some(i) = triangles.iter().position(contains) {
+                counts[i] += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// `n` points drawn uniformly from the area of the hull. A fan triangle is picked with a
+    /// probability proportional to its area and the point is drawn uniformly from that triangle.
+    /// Degenerate hulls have no area, their points are drawn uniformly from the segment instead.
+    pub fn sample_interior<R: Rng>(&self, rng: &mut R, n: usize) -> Vec<Point> {
+        if self.is_degenerate() {
+            let ends = self.canonical();
+            let (a, b) = (ends[0], ends[ends.len() - 1]);
+            return (0..n).map(|_| a + (b - a) * rng.gen::<f64>()).collect();
+        }
+
+        let triangles = self.triangulate_fan();
+        let areas = triangles.iter().map(|[a, b, c]| orient2d(a, b, c).max(0.0));
+        let pick = WeightedIndex::new(areas).unwrap();
+
+        (0..n)
+            .map(|_| {
+                let [a, b, c] = triangles[pick.sample(rng)];
+                let (mut u, mut v) = (rng.gen::<f64>(), rng.gen::<f64>());
+                // points beyond the diagonal are mirrored back into the triangle
+                if u + v > 1.0 {
+                    (u, v) = (1.0 - u, 1.0 - v);
+                }
+                a + (b - a) * u + (c - a) * v
+            })
+            .collect()
+    }
+
+    /// A `width` by `height` grid of unit cells with the origin in the corner of the first cell,
+    /// row by row with increasing y. A cell is true if its center lies inside or on the boundary.
+    /// Every row intersects its line through the cell centers with the hull once and fills the
+    /// cells in between, instead of testing every cell on its own.
+    pub fn rasterize(&self, width: usize, height: usize) -> Vec<bool> {
+        let mut mask = vec![false; width * height];
+        let centers: Vec<f64> = (0..height).map(|row| row as f64 + 0.5).collect();
+
+        for (cells, (left, right)) in mask.chunks_mut(width.max(1)).zip(self.scanline_spans(&centers)) {
+            if left > right {
+                continue;
+            }
+
+            // the columns whose center `column + 0.5` lies within the span, clamped to the grid
+            let first = (left - 0.5).ceil().max(0.0) as usize;
+            let last = ((right - 0.5).floor() + 1.0).clamp(0.0, width as f64) as usize;
+            if first < last {
+                cells[first..last].fill(true);
+            }
+        }
+
+        mask
+    }
+
+    /// For every y the interval `(left, right)` of x where the horizontal line meets the hull,
+    /// boundary included. A line which misses the hull gives `(inf, -inf)`, so `left > right`
+    /// marks it as empty and taking the union with another span by min and max still works.
+    pub fn scanline_spans(&self, y_values: &[f64]) -> Vec<(f64, f64)> {
+        y_values.iter().map(|&y| self.span(y)).collect()
+    }
+
+    fn span(&self, y: f64) -> (f64, f64) {
+        let n = self.hull.len();
+        (0..n)
+            .filter_map(|i| {
+                let (a, b) = (self.hull[i], self.hull[(i + 1) % n]);
+                if y < a.y.min(b.y) || y > a.y.max(b.y) {
+                    return None;
+                }
+
+                // a horizontal edge on the line only adds its start, the end is the start of the
+                // next edge which adds it exactly
+                if a.y == b.y {
+                    return Some(a.x);
+                }
+                Some(a.x + (b.x - a.x) * ((y - a.y) / (b.y - a.y)))
+            })
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(left, right), x| (left.min(x), right.max(x)))
+    }
+
+    /// Area enclosed by the hull, the shoelace formula is always positive as the hull is ccw.
+    pub fn area(&self) -> f64 {
+        let n = self.hull.len();
+        let twice_area: f64 = (0..n)
+            .map(|i| {
+                let s = &self.hull[i];
+                let e = &self.hull[(i + 1) % n];
+                s.x * e.y - e.x * s.y
+            })
+            .sum();
+
+        twice_area / 2.0
+    }
+
+    /// How much of the bounding box is covered by the hull, 1 for an axis aligned rectangle. Hulls
+    /// with an empty bounding box, i.e. a single point or an axis aligned segment, give 0.
+    pub fn bbox_fill_ratio(&self) -> f64 {
+        let bbox_area = (self.x_max - self.x_min) * (self.y_max - self.y_min);
+        if bbox_area == 0.0 {
+            return 0.0;
+        }
+
+        self.area() / bbox_area
+    }
+
+    /// The center of mass of the area enclosed by the hull, for a convex polygon it always lies
+    /// in the interior. Hulls without area use the average of their vertices instead.
+    pub fn centroid(&self) -> Point {
+        let origin = self.hull[0];
+        let mut area = 0.0;
+        let mut sum = Point::new(0.0, 0.0);
+
+        // every fan triangle adds its own centroid weighted by its signed area, relative to the
+        // first vertex to keep the products small
+        for i in 1..self.hull.len().saturating_sub(1) {
+            let (b, c) = (self.hull[i] - origin, self.hull[i + 1] - origin);
+            let weight = orient2d(&Point::new(0.0, 0.0), &b, &c);
+            area += weight;
+            sum += (b + c) * weight;
+        }
+
+        if area == 0.0 {
+            let total = self.hull.iter().fold(Point::new(0.0, 0.0), |a, b| a + b);
+            return total / self.hull.len() as f64;
+        }
+
+        origin + sum / (3.0 * area)
+    }
+
+    /// Center and radius of the largest circle which fits inside the hull, e.g. to place a label.
+    /// The center is the point whose smallest distance to the lines of the edges is the largest.
+    /// That distance is a concave function of the position, so it is maximized by a ternary search
+    /// over x where each step runs a ternary search over y. Degenerate hulls give their centroid
+    /// with a radius of 0.
+    pub fn largest_inscribed_circle(&self) -> (Point, f64) {
+        if self.is_degenerate() {
+            return (self.centroid(), 0.0);
+        }
+
+        let clearance = |x: f64, y: f64| self.clearance(&Point::new(x, y));
+        let best_y = |x: f64| ternary_search_max(self.y_min, self.y_max, |y| clearance(x, y));
+        let x = ternary_search_max(self.x_min, self.x_max, |x| clearance(x, best_y(x)));
+        let center = Point::new(x, best_y(x));
+
+        (center, self.clearance(&center).max(0.0))
+    }
+
+    // distance from p to the closest edge line, positive inside and negative outside, for points
+    // inside this is the distance to the boundary
+    fn clearance(&self, p: &Point) -> f64 {
+        let n = self.hull.len();
+        (0..n)
+            .filter(|&i| self.hull[i] != self.hull[(i + 1) % n])
+            .map(|i| {
+                let (s, e) = (&self.hull[i], &self.hull[(i + 1) % n]);
+                orient2d(s, e, p) / (e - s).magnitude()
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Unit normal of the edge from vertex `edge_index` to the next one, pointing away from the
+    /// hull. As the hull is ccw that is the right hand side of the edge, where
+    /// [`Orientation::calc`] of the edge and a point gives `Leftwards`. An edge of length 0, which
+    /// only a hull of a single point has, gives nan.
+    pub fn edge_outward_normal(&self, edge_index: usize) -> Point {
+        let n = self.hull.len();
+        let edge = self.hull[(edge_index + 1) % n] - self.hull[edge_index];
+        Point::new(edge.y, -edge.x).normalize()
+    }
+
+    /// Moves every edge by `distance` along its outward normal, a negative distance moves them
+    /// inwards. Growing keeps the corners sharp, the new vertices are where the moved edges meet.
+    /// Shrinking by at least the radius of [`ConvexPoly::largest_inscribed_circle`] collapses the
+    /// polygon, then the result is a segment or just the center of that circle. A degenerate hull
+    /// grows into a rectangle around it and collapses into its centroid when shrunk. The result
+    /// has no ids and only contains its hull vertices.
+    pub fn offset(&self, distance: f64) -> ConvexPoly {
+        if distance == 0.0 {
+            return ConvexPoly::from_valid(self.hull.clone());
+        }
+
+        if self.is_degenerate() {
+            return if distance > 0.0 {
+                ConvexPoly::from_valid(self.offset_degenerate(distance))
+            } else {
+                ConvexPoly::from_valid(vec![self.centroid()])
+            };
+        }
+
+        let n = self.hull.len();
+
+        if distance > 0.0 {
+            // every edge survives, so each corner moves to where its two moved edges intersect
+            let vertices = (0..n)
+                .filter(|&i| is_corner(&self.hull, i))
+                .map(|i| {
+                    let (a, b) = (self.edge_outward_normal((i + n - 1) % n), self.edge_outward_normal(i));
+                    self.hull[i] + (a + b) * (distance / (1.0 + a.dot(b)))
+                })
+                .collect();
+
+            return ConvexPoly::from_valid(vertices);
+        }
+
+        // inwards edges can vanish, clip the hull by the moved half-plane of every edge instead
+        let mut clipped = self.hull.clone();
+        for i in (0..n).filter(|&i| self.hull[i] != self.hull[(i + 1) % n]) {
+            let (s, e) = (self.hull[i], self.hull[(i + 1) % n]);
+            let length = (e - s).magnitude();
+            clipped = clip_half_plane(&clipped, |q| orient2d(&s, &e, q) / length + distance);
+
+            if clipped.is_empty() {
+                return ConvexPoly::from_valid(vec![self.largest_inscribed_circle().0]);
+            }
+        }
+
+        ConvexPoly::from_valid(clipped)
+    }
+
+    // the rectangle at `distance` around a hull which is a segment or a point
+    fn offset_degenerate(&self, distance: f64) -> Vec<Point> {
+        let start = self.hull[0];
+        let end = self.hull.iter()
+            .copied()
+            .max_by(|a, b| (a - start).magnitude2().total_cmp(&(b - start).magnitude2()))
+            .unwrap();
+
+        let along = if end == start { Point::new(1.0, 0.0) } else { (end - start).normalize() } * distance;
+        let across = Point::new(-along.y, along.x);
+
+        vec![start - along - across, end + along - across, end + along + across, start - along + across]
+    }
+
+    /// The second moments of area `(Ixx, Iyy, Ixy)` about the centroid, i.e. the integrals of
+    /// `y^2`, `x^2` and `x * y` over the hull with the centroid as origin. Hulls without area give 0.
+    pub fn second_moments(&self) -> (f64, f64, f64) {
+        let n = self.hull.len();
+        let center = self.centroid();
+        let (mut ixx, mut iyy, mut ixy) = (0.0, 0.0, 0.0);
+
+        // the usual sums over the edges, relative to the centroid so no parallel axis shift is needed
+        for i in 0..n {
+            let a = self.hull[i] - center;
+            let b = self.hull[(i + 1) % n] - center;
+            let cross = a.x * b.y - b.x * a.y;
+
+            ixx += cross * (a.y * a.y + a.y * b.y + b.y * b.y);
+            iyy += cross * (a.x * a.x + a.x * b.x + b.x * b.x);
+            ixy += cross * (a.x * b.y + 2.0 * a.x * a.y + 2.0 * b.x * b.y + b.x * a.y);
+        }
+
+        (ixx / 12.0, iyy / 12.0, ixy / 24.0)
+    }
+
+    /// The largest angle between two consecutive hull vertices as seen from the centroid, 2π/n for
+    /// a regular polygon. Vertices at the centroid itself, which only degenerate hulls have, have
+    /// no direction and are skipped, so a single point gives a gap of 2π.
+    pub fn max_angular_gap(&self) -> f64 {
+        let center = self.centroid();
+        let angles: Vec<f64> = self.hull.iter()
+            .filter(|h| **h != center)
+            .map(|h| angle(&(h - center)))
+            .collect();
+
+        let n = angles.len();
+        if n < 2 {
+            return 2.0 * f64::PI();
+        }
+
+        (0..n)
+            .map(|i| wrapped_angle_sub(angles[(i + 1) % n], angles[i]))
+            .fold(0.0, f64::max)
+    }
+
+    /// The point inside the hull around which [`is_point_in_polygon_fast`] measures its angles,
+    /// the centroid of the first triangle of hull vertices which has an area. Only degenerate
+    /// hulls without any area put it on the boundary.
+    pub fn pivot(&self) -> Point {
+        let [a, b, c] = self.pivot_triangle();
+        (a + b + c) / 3.0
+    }
+
+    // the triangle whose centroid is the pivot, found in O(log n)
+    fn pivot_triangle(&self) -> [Point; 3] {
+        // the vertices on the line of the first edge are a prefix of the hull
+        let n = self.hull.len();
+        let k = 2 + self.hull[2.min(n)..]
+            .partition_point(|p| Orientation::calc(&self.hull[0], p, &self.hull[1]) == Collinear);
+
+        [self.hull[0], self.hull[1 % n], self.hull[k.min(n - 1)]]
+    }
+
+    // whether the pivot triangle has an area, which is the case exactly if the hull is not
+    // degenerate, in O(log n) instead of the O(n) of `is_degenerate`
+    fn has_area(&self) -> bool {
+        let [a, b, c] = self.pivot_triangle();
+        Orientation::calc(&a, &b, &c) != Collinear
+    }
+
+    /// Precomputes the setup of [`is_point_in_polygon_fast`] for many containment queries.
+    pub fn query_context(&self) -> QueryContext {
+        let center = self.pivot();
+        let offset_angle = angle(&(self.hull[0] - center));
+
+        QueryContext {
+            hull: self.hull.clone(),
+            bbox: self.bounding_box(),
+            has_area: self.has_area(),
+            center,
+            offset_angle,
+            angles: self.hull.iter()
+                .map(|h| wrapped_angle_sub(angle(&(h - center)), offset_angle))
+                .collect(),
+        }
+    }
+
+    /// Indices of the two hull vertices of the edge whose angular sector, as seen from the interior
+    /// pivot used by [`is_point_in_polygon_fast`], contains `p`. The edge is given in ccw order.
+    pub fn nearest_hull_edge(&self, p: &Point) -> (usize, usize) {
+        let n = self.hull.len();
+        let (closest, search_angle, closest_angle) = closest_node_by_angle(self, p);
+
+        if search_angle >= closest_angle {
+            (closest, (closest + 1) % n)
+        } else {
+            ((closest + n - 1) % n, closest)
+        }
+    }
+
+    /// The hull vertex which lies the farthest in `direction`, i.e. has the largest dot product
+    /// with it. The edges of the ccw hull starting at its lowest vertex have increasing angles, so
+    /// the vertex where they pass the tangent direction is found with a binary search.
+    pub fn support(&self, direction: Point) -> Point {
+        self.hull[self.support_index(direction)]
+    }
+
+    fn support_index(&self, direction: Point) -> usize {
+        let n = self.hull.len();
+        let edge_angle = |i: usize| angle(&(self.hull[(i + 1) % n] - self.hull[i]));
+        let tangent = (angle(&direction) + f64::FRAC_PI_2()).rem_euclid(2.0 * f64::PI());
+
+        // the first edge pointing at or past the tangent starts at the supporting vertex, if
+        // there is none the tangent lies between the last and the first edge
+        let mut low = 0;
+        let mut high = n;
+        while low < high {
+            let middle = low + (high - low) / 2;
+
+            if edge_angle(middle) < tangent {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        // rounding of the angles can be off by a vertex, climb to the actual maximum
+        let dot = |i: usize| self.hull[i].dot(direction);
+        let mut best = low % n;
+        loop {
+            let next = (best + 1) % n;
+            let prev = (best + n - 1) % n;
+
+            if dot(next) > dot(best) {
+                best = next;
+            } else if dot(prev) > dot(best) {
+                best = prev;
+            } else {
+                return best;
+            }
+        }
+    }
+
+    /// Extent of the hull along `direction`, the distance between the two supporting lines
+    /// perpendicular to it. `direction` doesn't have to be a unit vector, it is normalized.
+    pub fn width_in_direction(&self, direction: Point) -> f64 {
+        let direction = direction.normalize();
+        (self.support(direction) - self.support(-direction)).dot(direction)
+    }
+
+    /// The rectangle with the smallest area which contains the hull, its corners in ccw order. One
+    /// side of it always lies on a hull edge so only the edge directions are tried, each with the
+    /// extents found by [`ConvexPoly::support`]. A degenerate hull gives a rectangle without area.
+    pub fn min_area_rect(&self) -> [Point; 4] {
+        let n = self.hull.len();
+        let rect = |u: Point| {
+            let v = Point::new(-u.y, u.x);
+            let (u0, u1) = (self.support(-u).dot(u), self.support(u).dot(u));
+            let (v0, v1) = (self.support(-v).dot(v), self.support(v).dot(v));
+            let corners = [u * u0 + v * v0, u * u1 + v * v0, u * u1 + v * v1, u * u0 + v * v1];
+            ((u1 - u0) * (v1 - v0), corners)
+        };
+
+        // a single vertex has no edges, any rectangle around it is as good as another
+        (0..n)
+            .map(|i| self.hull[(i + 1) % n] - self.hull[i])
+            .filter(|e| e.magnitude2() > 0.0)
+            .map(|e| rect(e.normalize()))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .unwrap_or_else(|| rect(Point::new(1.0, 0.0)))
+            .1
+    }
+
+    /// Width divided by height of the bounding box. A horizontal segment gives infinity, a vertical
+    /// one 0 and a single point NaN as its box is empty in both directions.
+    pub fn aspect_ratio(&self) -> f64 {
+        (self.x_max - self.x_min) / (self.y_max - self.y_min)
+    }
+
+    /// Angle in `[0, pi)` of the longer side of [`ConvexPoly::min_area_rect`], the direction in
+    /// which the hull is elongated regardless of how it is rotated.
+    pub fn principal_orientation(&self) -> f64 {
+        let [a, b, c, _] = self.min_area_rect();
+        let side = if (b - a).magnitude2() >= (c - b).magnitude2() { b - a } else { c - b };
+        side.y.atan2(side.x).rem_euclid(f64::PI())
+    }
+
+    /// All pairs of hull vertices through which two parallel supporting lines can be placed,
+    /// found by one rotating calipers sweep. Every pair is listed once as `(i, j)` with `i < j`.
+    pub fn antipodal_pairs(&self) -> Vec<(usize, usize)> {
+        let n = self.hull.len();
+
+        // vertices in the middle of an edge only ever touch a caliper together with the whole edge,
+        // so the sweep runs over the corners and expands an edge into all of its vertices
+        let corners: Vec<usize> = (0..n)
+            .filter(|&i| is_corner(&self.hull, i))
+            .collect();
+        let m = corners.len();
+
+        if m < 3 {
+            // a segment or a point, a caliper perpendicular to it touches every vertex
+            return (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))).collect();
+        }
+
+        let corner = |k: usize| corners[k % m];
+        let edge = |k: usize| self.hull[corner(k + 1)] - self.hull[corner(k)];
+        let run = |k: usize| {
+            let (a, b) = (corner(k), corner(k + 1));
+            (0..=(b + n - a) % n).map(move |o| (a + o) % n)
+        };
+
+        // the caliper through the first corner starts out pointing along +x and the opposite one
+        // along -x through the topmost corner, the rightmost of them if the top is an edge
+        let top = (0..m)
+            .max_by(|&a, &b| {
+                let (a, b) = (self.hull[corners[a]], self.hull[corners[b]]);
+                a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+            })
+            .unwrap();
+        let (mut i, mut j) = (0, top);
+        let mut pairs = vec![];
+
+        // both calipers turn ccw by half a revolution, which passes every edge exactly once
+        let mut steps = 0;
+        while steps < m {
+            let turn = edge(i).perp_dot(-edge(j));
+
+            if turn > 0.0 {
+                pairs.extend(run(i).map(|a| (a, corner(j))));
+                i += 1;
+                steps += 1;
+            } else if turn < 0.0 {
+                pairs.extend(run(j).map(|b| (corner(i), b)));
+                j += 1;
+                steps += 1;
+            } else {
+                // parallel edges, every vertex of one is antipodal to every vertex of the other
+                pairs.extend(run(i).flat_map(|a| run(j).map(move |b| (a, b))));
+                i += 1;
+                j += 1;
+                steps += 2;
+            }
+        }
+
+        let mut pairs: Vec<(usize, usize)> = pairs.into_iter()
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        pairs.sort();
+        pairs.dedup();
+        pairs
+    }
+
+    /// The two hull vertices which are the farthest apart, their distance is the diameter.
+    pub fn diameter(&self) -> (Point, Point) {
+        let (i, j) = self.diameter_indices();
+        (self.hull[i], self.hull[j])
+    }
+
+    /// Indices of the vertices of [`ConvexPoly::diameter`], the farthest pair is always antipodal so
+    /// only the pairs of [`ConvexPoly::antipodal_pairs`] are compared. A single vertex gives `(0, 0)`.
+    pub fn diameter_indices(&self) -> (usize, usize) {
+        let distance2 = |&(i, j): &(usize, usize)| (self.hull[i] - self.hull[j]).magnitude2();
+
+        self.antipodal_pairs()
+            .into_iter()
+            .max_by(|a, b| distance2(a).total_cmp(&distance2(b)))
+            .unwrap_or((0, 0))
+    }
+
+    /// Index of the hull vertex closest to `p` together with its distance. This is a linear scan,
+    /// the distance to the vertices of a convex polygon isn't unimodal along the boundary (for a
+    /// point near the center of a rectangle every corner is a local minimum) so a binary search
+    /// could stop at the wrong vertex.
+    pub fn nearest_vertex(&self, p: &Point) -> (usize, f64) {
+        let (index, distance2) = self.hull.iter()
+            .map(|h| (h - p).magnitude2())
+            .enumerate()
+            .fold((0, f64::INFINITY), |best, (i, d)| if d < best.1 { (i, d) } else { best });
+
+        (index, distance2.sqrt())
+    }
+
+    /// The hull vertices with the largest y, the smallest y, the largest x and the smallest x in
+    /// that order, i.e. the vertices which lie on the bounding box.
+    pub fn extremes(&self) -> [Point; 4] {
+        [
+            self.support(Point::new(0.0, 1.0)),
+            self.support(Point::new(0.0, -1.0)),
+            self.support(Point::new(1.0, 0.0)),
+            self.support(Point::new(-1.0, 0.0)),
+        ]
+    }
+
+    /// Indices of the two hull vertices where the tangents from `p` touch the hull, the part of the
+    /// boundary which faces `p` runs ccw from the first to the second one. `None` if `p` lies inside
+    /// or on the boundary, and for degenerate hulls.
+    pub fn tangent_vertices(&self, p: &Point) -> Option<(usize, usize)> {
+        let n = self.hull.len();
+        if self.is_degenerate() || is_point_in_polygon(self, p, Inclusion::Inclusive) {
+            return None;
+        }
+
+        // p sees an edge if it lies strictly outside of its line, the visible edges form one chain
+        let visible = |i: usize| Orientation::calc(&self.hull[i % n], p, &self.hull[(i + 1) % n]) == Leftwards;
+
+        // as seen from the pivot of the angular search the edge towards p is visible and the one
+        // in the opposite direction is not, so each end of the chain lies between those two
+        let pivot = self.pivot();
+        let (towards, _) = self.nearest_hull_edge(p);
+        let (away, _) = self.nearest_hull_edge(&(pivot * 2.0 - p));
+
+        if !visible(towards) || visible(away) {
+            // rounding in the angular search can pick a neighbouring edge, fall back to a scan
+            let first = (0..n).find(|&i| visible(i) && !visible(i + n - 1))?;
+            let last = (0..n).find(|&i| visible(i) && !visible(i + 1))?;
+            return Some((first, (last + 1) % n));
+        }
+
+        // the last visible edge after `towards`, the chain ends at its second vertex
+        let (mut low, mut high) = (0, (away + n - towards) % n);
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+
+            if visible(towards + middle) {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        let last = (towards + high) % n;
+
+        // the first visible edge after `away`, the chain starts at its first vertex
+        let (mut low, mut high) = (0, (towards + n - away) % n);
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+
+            if visible(away + middle) {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+        let first = (away + high) % n;
+
+        Some((first, last))
+    }
+
+    /// Length of the shorter of the two tangents from `p` to the hull, measured to the vertex
+    /// of [`ConvexPoly::tangent_vertices`] it touches. `None` in the same cases as there, for
+    /// points inside or on the boundary and for degenerate hulls.
+    pub fn tangent_distance(&self, p: &Point) -> Option<f64> {
+        let (a, b) = self.tangent_vertices(p)?;
+        Some((self.hull[a] - p).magnitude().min((self.hull[b] - p).magnitude()))
+    }
+
+    /// Indices of the edges which `p` lies strictly outside of, edge `i` runs from vertex `i` to the
+    /// next one. They form one chain which is listed in ccw order, for a point inside or on the
+    /// boundary there are none.
+    pub fn visible_edges(&self, p: &Point) -> Vec<usize> {
+        let n = self.hull.len();
+        let visible = |i: usize| Orientation::calc(&self.hull[i], p, &self.hull[(i + 1) % n]) == Leftwards;
+
+        // the chain can wrap around the first vertex, so it is listed from where it starts
+        let start = (0..n)
+            .find(|&i| visible(i) && !visible((i + n - 1) % n))
+            .unwrap_or(0);
+
+        (0..n)
+            .map(|k| (start + k) % n)
+            .filter(|&i| visible(i))
+            .collect()
+    }
+
+    /// Distance from `p` to the hull boundary, positive outside and negative inside.
+    pub fn signed_distance(&self, p: &Point) -> f64 {
+        let n = self.hull.len();
+        let distance = (0..n)
+            .map(|i| point_segment_distance(p, &self.hull[i], &self.hull[(i + 1) % n]))
+            .fold(f64::INFINITY, f64::min);
+
+        if is_point_in_polygon(self, p, Inclusion::Inclusive) { -distance } else { distance }
+    }
+
+    /// The symmetric hausdorff distance between the boundaries of both hulls, the largest distance
+    /// from a point on one boundary to the closest point on the other one.
+    pub fn hausdorff_distance(&self, other: &ConvexPoly) -> f64 {
+        self.directed_hausdorff_distance(other).max(other.directed_hausdorff_distance(self))
+    }
+
+    // the largest distance from a point on this boundary to the boundary of other
+    fn directed_hausdorff_distance(&self, other: &ConvexPoly) -> f64 {
+        let n = self.hull.len();
+        // outside of other the distance to it is convex along an edge, so there the farthest
+        // points are vertices
+        let vertices = self.hull.iter()
+            .map(|p| other.signed_distance(p).abs())
+            .fold(0.0, f64::max);
+
+        if other.is_degenerate() {
+            return vertices;
+        }
+
+        // inside it is the clearance which is concave along an edge, its maximum is searched
+        let edges = (0..n)
+            .map(|i| {
+                let (s, e) = (self.hull[i], self.hull[(i + 1) % n]);
+                let t = ternary_search_max(0.0, 1.0, |t| other.clearance(&(s + (e - s) * t)));
+                other.clearance(&(s + (e - s) * t))
+            })
+            .fold(0.0, f64::max);
+
+        vertices.max(edges)
+    }
+
+    /// Indices of the points which lie outside of the hull and more than `distance` away from it.
+    pub fn outliers_beyond(&self, points: &[Point], distance: f64) -> Vec<usize> {
+        (0..points.len())
+            .filter(|&i| self.signed_distance(&points[i]) > distance)
+            .collect()
+    }
+
+    /// Applies the affine transform `m` in homogeneous coordinates to every point. Affine maps keep
+    /// the hull convex so it isn't rebuilt, a reflection turns it cw which is undone by reversing it.
+    pub fn transform(&self, m: &Mat) -> ConvexPoly {
+        let apply = |p: &Point| {
+            let v = *m * Vector3::new(p.x, p.y, 1.0);
+            Point::new(v.x, v.y)
+        };
+
+        let mut hull: Vec<Point> = self.hull.iter().map(apply).collect();
+        let mut hull_ids = self.hull_ids.clone();
+
+        // determinant of the linear part, cgmath matrices are stored column by column
+        if m.x.x * m.y.y - m.y.x * m.x.y < 0.0 {
+            hull.reverse();
+            hull_ids.reverse();
+        }
+
+        let start = rotate_to_lowest(&mut hull);
+        if !hull_ids.is_empty() {
+            hull_ids.rotate_left(start);
+        }
+
+        let mut poly = Self::from_hull(self.all.iter().map(apply).collect(), hull);
+        poly.hull_ids = hull_ids;
+        poly
+    }
+
+    /// Mirrors the polygon across the line through `line_point` along `line_dir`, see
+    /// [`ConvexPoly::transform`] for how the winding is kept ccw.
+    pub fn reflect(&self, line_point: Point, line_dir: Point) -> ConvexPoly {
+        let m = translation(line_point.x, line_point.y) * reflection(line_dir) * translation(-line_point.x, -line_point.y);
+        self.transform(&m)
+    }
+
+    /// Whether the origin lies inside or on the boundary, as needed e.g. by gjk. For a single fixed
+    /// point setting up the angular search of [`is_point_in_polygon_fast`] doesn't pay off, so this
+    /// checks the side of every edge and stops at the first one which has the origin outside.
+    pub fn contains_origin(&self) -> bool {
+        is_point_in_polygon(self, &Point::new(0.0, 0.0), Inclusion::Inclusive)
+    }
+
+    /// Whether every vertex of `other` lies inside or on the boundary of this polygon, as both are
+    /// convex this means `other` is contained completely.
+    pub fn contains_polygon(&self, other: &ConvexPoly) -> bool {
+        other.hull.iter().all(|p| is_point_in_polygon(self, p, Inclusion::Inclusive))
+    }
+
+    /// The share of `points` which lie strictly inside according to [`is_point_in_polygon_fast`],
+    /// 0 for no points. Degenerate hulls have no inside.
+    pub fn fraction_inside(&self, points: &[Point]) -> f64 {
+        if points.is_empty() || self.is_degenerate() {
+            return 0.0;
+        }
+
+        let inside = points.iter().filter(|p| is_point_in_polygon_fast(self, p, Inclusion::Exclusive)).count();
+        inside as f64 / points.len() as f64
+    }
+
+    /// Convex hull of both polygons. Instead of re-hulling everything the vertices of both hulls
+    /// are merged in x order and run through a monotone chain, so this is linear in the number
+    /// of hull vertices. Collinear vertices are dropped from the merged hull.
+    pub fn merge(&self, other: &ConvexPoly) -> ConvexPoly {
+        let sorted = merge_sorted(&lexicographic_vertices(&self.hull), &lexicographic_vertices(&other.hull));
+        let mut hull = monotone_chain(&sorted);
+        rotate_to_lowest(&mut hull);
+
+        ConvexPoly {
+            all: self.all.iter().chain(&other.all).copied().collect(),
+            hull,
+            hull_ids: vec![],
+            x_min: self.x_min.min(other.x_min),
+            x_max: self.x_max.max(other.x_max),
+            y_min: self.y_min.min(other.y_min),
+            y_max: self.y_max.max(other.y_max),
+        }
+    }
+
+    /// The set of all sums `a + b` with `a` in this polygon and `b` in `other`. Both hulls start at
+    /// their lowest vertex and their edges are sorted by angle, so merging the two edge sequences
+    /// walks the boundary of the sum in linear time. Degenerate hulls don't have edges with
+    /// increasing angles, for them the sums of all vertex pairs are hulled instead.
+    pub fn minkowski_sum(&self, other: &ConvexPoly) -> ConvexPoly {
+        if self.is_degenerate() || other.is_degenerate() {
+            let sums = self.hull.iter().flat_map(|a| other.hull.iter().map(move |b| a + b)).collect();
+            return Self::from_valid(sums);
+        }
+
+        let (p, q) = (&self.hull, &other.hull);
+        let (n, m) = (p.len(), q.len());
+        let edge = |hull: &[Point], i: usize| hull[(i + 1) % hull.len()] - hull[i % hull.len()];
+
+        let mut hull = Vec::with_capacity(n + m);
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            hull.push(p[i % n] + q[j % m]);
+
+            // take the edge with the smaller angle, both if they are parallel
+            let turn = edge(p, i).perp_dot(edge(q, j));
+            if turn >= 0.0 && i < n {
+                i += 1;
+            }
+            if turn <= 0.0 && j < m {
+                j += 1;
+            }
+        }
+
+        Self::from_hull(hull.clone(), hull)
+    }
+
+    /// The minkowski sum of this polygon and `other` mirrored through the origin, i.e. all
+    /// differences `a - b`. The polygons intersect exactly if the difference contains the origin,
+    /// see [`ConvexPoly::contains_origin`], which is what gjk tests for.
+    pub fn minkowski_difference(&self, other: &ConvexPoly) -> ConvexPoly {
+        self.minkowski_sum(&other.transform(&scaling(-1.0, -1.0)))
+    }
+
+    /// The smallest distance between a point of this polygon and one of `other`, 0 if they touch
+    /// or overlap. Every such distance is the length of a point in the minkowski difference, so
+    /// this is the distance from the origin to it.
+    pub fn distance_to(&self, other: &ConvexPoly) -> f64 {
+        // adding zero turns the -0.0 of touching polygons into 0.0
+        self.minkowski_difference(other)
+            .signed_distance(&Point::new(0.0, 0.0))
+            .max(0.0) + 0.0
+    }
+
+    /// Drops the hull vertices which lie on the segment between their neighbours up to the default
+    /// epsilon of the bounding box, so only the corners remain. The point set, the bounds and the
+    /// ids of the remaining vertices are kept.
+    pub fn remove_collinear_vertices(&mut self) {
+        let eps = self.bounding_box().epsilon();
+        let on_segment = |i: usize, a: usize, b: usize| {
+            point_segment_distance(&self.hull[i], &self.hull[a], &self.hull[b]) <= eps
+        };
+
+        let mut kept: Vec<usize> = vec![];
+        for i in 0..self.hull.len() {
+            while kept.len() >= 2 && on_segment(kept[kept.len() - 1], kept[kept.len() - 2], i) {
+                kept.pop();
+            }
+            kept.push(i);
+        }
+
+        // the ring closes between the last and the first vertex
+        while kept.len() >= 3 && on_segment(kept[kept.len() - 1], kept[kept.len() - 2], kept[0]) {
+            kept.pop();
+        }
+        while kept.len() >= 3 && on_segment(kept[0], kept[kept.len() - 1], kept[1]) {
+            kept.remove(0);
+        }
+
+        self.hull = kept.iter().map(|&i| self.hull[i]).collect();
+        if !self.hull_ids.is_empty() {
+            self.hull_ids = kept.iter().map(|&i| self.hull_ids[i]).collect();
+        }
+
+        let start = rotate_to_lowest(&mut self.hull);
+        if !self.hull_ids.is_empty() {
+            self.hull_ids.rotate_left(start);
+        }
+    }
+
+    /// Removes one occurrence of `p` from the point set and repairs the hull if `p` was one of its
+    /// vertices, returns whether anything was removed. Only the points in the triangle spanned by
+    /// the removed vertex and its two neighbours can become new hull vertices, so only those are
+    /// re-hulled and small hulls get a full rebuild. A polygon always keeps at least one point,
+    /// removing the last one is refused and returns false.
+    pub fn remove_point(&mut self, p: &Point) -> bool {
+        let Some(index) = self.all.iter().position(|a| a == p) else {
+            return false;
+        };
+
+        if self.all.len() == 1 {
+            return false;
+        }
+        self.all.remove(index);
+
+        self.x_min = self.all.iter().map(|p| p.x).reduce(|a, b| a.min(b)).unwrap();
+        self.x_max = self.all.iter().map(|p| p.x).reduce(|a, b| a.max(b)).unwrap();
+        self.y_min = self.all.iter().map(|p| p.y).reduce(|a, b| a.min(b)).unwrap();
+        self.y_max = self.all.iter().map(|p| p.y).reduce(|a, b| a.max(b)).unwrap();
+
+        // a duplicate of p is still there, so the hull stays the same
+        let Some(vertex) = self.hull.iter().position(|h| h == p).filter(|_| !self.all.contains(p)) else {
+            return true;
+        };
+
+        if self.hull.len() <= 3 {
+            *self = ConvexPoly::from_valid(std::mem::take(&mut self.all));
+            return true;
+        }
+
+        let n = self.hull.len();
+        let prev = self.hull[(vertex + n - 1) % n];
+        let next = self.hull[(vertex + 1) % n];
+
+        let candidates: Vec<Point> = self.all.iter()
+            .filter(|c| {
+                Orientation::calc(&prev, c, p) != Leftwards &&
+                    Orientation::calc(p, c, &next) != Leftwards &&
+                    Orientation::calc(&next, c, &prev) != Leftwards
+            })
+            .copied()
+            .collect();
+
+        // the local hull is ccw as well, its chain from prev to next replaces the removed vertex
+        let local = ConvexPoly::from_valid(candidates).hull;
+        let start = local.iter().position(|h| *h == prev);
+        let end = local.iter().position(|h| *h == next);
+
+        let (Some(start), Some(end)) = (start, end) else {
+            *self = ConvexPoly::from_valid(std::mem::take(&mut self.all));
+            return true;
+        };
+
+        let chain: Vec<Point> = (1..)
+            .map(|i| local[(start + i) % local.len()])
+            .take((end + local.len() - start) % local.len() - 1)
+            .collect();
+
+        self.hull.splice(vertex..vertex + 1, chain);
+        rotate_to_lowest(&mut self.hull);
+        self.hull_ids.clear();
+        true
+    }
+}
+
+/// Hulls are equal if their [`ConvexPoly::canonical`] forms match within the default epsilon of
+/// the larger bounding box, the point sets and ids aren't compared.
+impl PartialEq for ConvexPoly {
+    fn eq(&self, other: &Self) -> bool {
+        let eps = self.bounding_box().epsilon().max(other.bounding_box().epsilon());
+        let (a, b) = (self.canonical(), other.canonical());
+
+        a.len() == b.len() && a.iter().zip(&b).all(|(p, q)| points_approx_eq(p, q, eps))
+    }
+}
+
+// sorts the points ccw around the lowest point and runs the graham scan over them,
+// `step` gets to see the stack after every push and pop
+pub(crate) fn graham_scan(points: &mut [Point], step: impl FnMut(&[Point])) -> Vec<Point> {
+    graham_scan_by(points, |p| *p, step)
+}
+
+// graham scan over anything with a position, so extra data like ids stays with its point while sorting
+fn graham_scan_by<T: Copy>(items: &mut [T], pos: impl Fn(&T) -> Point, mut step: impl FnMut(&[T])) -> Vec<T> {
+    let mut hull = vec![];
+    // ties are broken by x so the start is the lowest leftmost point and no other point lies at an angle of pi
+    items.sort_by(|a, b| {
+        let (a, b) = (pos(a), pos(b));
+        a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+    });
+    hull.push(items[0]);
+    step(&hull);
+
+    // points on the same ray from the start are sorted by their distance, this puts duplicates
+    // right next to each other
+    let smallest = pos(&items[0]);
+    let key = |t: &T| {
+        let p = pos(t);
+        (angle(&(p - smallest)), (p - smallest).magnitude2())
+    };
+    items[1..].sort_by(|a, b| {
+        let (aa, ad) = key(a);
+        let (ba, bd) = key(b);
+        aa.total_cmp(&ba).then(ad.total_cmp(&bd))
+    });
+
+    // the points on the last ray are walked back towards the start, unless all points lie on one ray
+    let last_angle = key(&items[items.len() - 1]).0;
+    let mut k = items.len() - 1;
+    while k > 1 && key(&items[k - 1]).0 == last_angle {
+        k -= 1;
+    }
+    if k > 1 {
+        items[k..].reverse();
+    }
+
+    let eps = Aabb::from_points(items.iter().map(&pos)).unwrap().epsilon();
+
+    for t in &items[1..] {
+        let p = pos(t);
+        if points_approx_eq(&pos(&hull[hull.len() - 1]), &p, eps) {
+            continue;
+        }
+
+        while hull.len() > 1 &&
+            Orientation::calc(&pos(&hull[hull.len() - 2]), &pos(&hull[hull.len() - 1]), &p) == Rightwards {
+            hull.pop();
+            step(&hull);
+        }
+
+        hull.push(*t);
+        step(&hull);
+    }
+
+    hull
+}
+
+fn validate<'a>(points: impl Iterator<Item = &'a Point>) -> Result<(), HullError> {
+    let mut empty = true;
+
+    for (index, p) in points.enumerate() {
+        if !p.x.is_finite() || !p.y.is_finite() {
+            return Err(HullError::NonFinite { index });
+        }
+        empty = false;
+    }
+
+    if empty { Err(HullError::Empty) } else { Ok(()) }
+}
+
+// the first vertex at which the polygon stops being a convex hull turning towards `turn`, i.e.
+// where it turns the other way, repeats the previous vertex or starts winding around a second
+// time. A ccw hull turns `Leftwards` at every corner.
+fn first_non_convex(hull: &[Point], turn: Orientation) -> Option<usize> {
+    let n = hull.len();
+    if n == 1 {
+        return None;
+    }
+
+    let edge = |i: usize| hull[(i + 1) % n] - hull[i];
+    let bad_turn = (0..n).find(|&i| {
+        edge(i) == Point::new(0.0, 0.0) ||
+            Orientation::calc(&hull[(i + n - 1) % n], &hull[i], &hull[(i + 1) % n]) == turn.flip()
+    });
+    if bad_turn.is_some() {
+        return bad_turn;
+    }
+
+    // the edge directions only turn one way, going around once flips the sign of their x and y
+    // components exactly twice each
+    let components: [fn(&Point) -> f64; 2] = [|p| p.x, |p| p.y];
+    for component in components {
+        let signs: Vec<(usize, bool)> = (0..n)
+            .map(|i| (i, component(&edge(i))))
+            .filter(|&(_, c)| c != 0.0)
+            .map(|(i, c)| (i, c > 0.0))
+            .collect();
+
+        let m = signs.len();
+        let flips: Vec<usize> = (0..m)
+            .filter(|&k| signs[k].1 != signs[(k + m - 1) % m].1)
+            .map(|k| signs[k].0)
+            .collect();
+
+        if flips.len() > 2 {
+            return Some(flips[2]);
+        }
+    }
+
+    None
+}
+
+// whether the hull turns at vertex i, instead of it lying on the edge between its neighbours
+fn is_corner(hull: &[Point], i: usize) -> bool {
+    let n = hull.len();
+    Orientation::calc(&hull[(i + n - 1) % n], &hull[i], &hull[(i + 1) % n]) != Collinear
+}
+
+// rotates the hull so it starts at its lowest vertex, the leftmost one if there are several, like
+// the graham scan does, returns by how much it was rotated
+fn rotate_to_lowest(hull: &mut [Point]) -> usize {
+    let start = (0..hull.len())
+        .min_by(|&a, &b| hull[a].y.total_cmp(&hull[b].y).then(hull[a].x.total_cmp(&hull[b].x)))
+        .unwrap_or(0);
+    hull.rotate_left(start);
+    start
+}
+
+// argument in `low..=high` where the concave function f is the largest, 100 steps shrink the
+// range by a factor of about 1e-18 which is below the resolution of an f64
+fn ternary_search_max(mut low: f64, mut high: f64, f: impl Fn(f64) -> f64) -> f64 {
+    for _ in 0..100 {
+        let a = low + (high - low) / 3.0;
+        let b = high - (high - low) / 3.0;
+
+        if f(a) < f(b) {
+            low = a;
+        } else {
+            high = b;
+        }
+    }
+
+    (low + high) / 2.0
+}
+
+// merges two lists which are sorted by `cmp_points_xy`, duplicates are only kept once
+fn merge_sorted(a: &[Point], b: &[Point]) -> Vec<Point> {
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() || j < b.len() {
+        let next = if j == b.len() || (i < a.len() && cmp_points_xy(&a[i], &b[j]) != Ordering::Greater) {
+            i += 1;
+            a[i - 1]
+        } else {
+            j += 1;
+            b[j - 1]
+        };
+
+        if merged.last() != Some(&next) {
+            merged.push(next);
+        }
+    }
+
+    merged
+}
+
+// the vertices of a ccw hull sorted by `cmp_points_xy` in linear time, walking ccw from the
+// smallest to the largest vertex gives the lower chain and walking back gives the upper chain,
+// both of them are sorted already and only need to be merged
+fn lexicographic_vertices(hull: &[Point]) -> Vec<Point> {
+    let n = hull.len();
+    let min = (0..n).min_by(|&a, &b| cmp_points_xy(&hull[a], &hull[b])).unwrap();
+    let max = (0..n).max_by(|&a, &b| cmp_points_xy(&hull[a], &hull[b])).unwrap();
+
+    let lower: Vec<Point> = (0..=(max + n - min) % n).map(|k| hull[(min + k) % n]).collect();
+    let upper: Vec<Point> = (0..=(min + n - max) % n).map(|k| hull[(max + k) % n]).rev().collect();
+
+    merge_sorted(&lower, &upper)
+}
+
+// andrew's monotone chain over points sorted by `cmp_points_xy` without duplicates, the result is ccw
+// and starts at the smallest point, collinear points are dropped
+fn monotone_chain(sorted: &[Point]) -> Vec<Point> {
+    if sorted.len() < 3 {
+        return sorted.to_vec();
+    }
+
+    let mut lower = monotone_half(sorted.iter());
+    let mut upper = monotone_half(sorted.iter().rev());
+
+    // the last point of each half is the first of the other one
+    lower.pop();
+    upper.pop();
+    lower.append(&mut upper);
+    lower
+}
+
+// one half of the monotone chain, walking the sorted points forwards gives the lower chain and
+// walking them backwards the upper one
+fn monotone_half<'a>(points: impl Iterator<Item = &'a Point>) -> Vec<Point> {
+    let mut chain: Vec<Point> = vec![];
+    for p in points {
+        while chain.len() >= 2 && Orientation::calc(&chain[chain.len() - 2], p, &chain[chain.len() - 1]) != Rightwards {
+            chain.pop();
+        }
+        chain.push(*p);
+    }
+    chain
+}
+
+// sorted by `cmp_points_xy` with exact duplicates removed, what `monotone_chain` expects
+fn sorted_unique(points: &[Point]) -> Vec<Point> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(cmp_points_xy);
+    sorted.dedup();
+    sorted
+}
+
+/// Collects the points for a hull from different sources, on [`build`] points closer than the
+/// epsilon are merged and the input is validated like in [`ConvexPoly::new`].
+///
+/// [`build`]: ConvexPolyBuilder::build
+#[derive(Debug, Clone, Default)]
+pub struct ConvexPolyBuilder {
+    points: Vec<Point>,
+    // none means the default relative to the bounding box of the points
+    epsilon: Option<f64>,
+}
+
+impl ConvexPolyBuilder {
+    pub fn point(mut self, x: f64, y: f64) -> Self {
+        self.points.push(Point::new(x, y));
+        self
+    }
+
+    pub fn points(mut self, points: impl IntoIterator<Item = Point>) -> Self {
+        self.points.extend(points);
+        self
+    }
+
+    /// Points at most this far apart are merged, the default is [`suggested_epsilon`] of all points.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = Some(epsilon);
+        self
+    }
+
+    pub fn build(self) -> Result<ConvexPoly, HullError> {
+        validate(self.points.iter())?;
+
+        let eps = self.epsilon.unwrap_or_else(|| suggested_epsilon(&self.points));
+        Ok(ConvexPoly::from_valid(dedup_points(self.points, eps)))
+    }
+}
+
+/// Lets any iterator of points be collected straight into a hull with `collect_hull`.
+pub trait HullExt: Iterator<Item = Point> + Sized {
+    /// Same as [`ConvexPoly::new`] over all points of the iterator.
+    fn collect_hull(self) -> Result<ConvexPoly, HullError> {
+        ConvexPoly::new(self.collect())
+    }
+}
+
+impl<I: Iterator<Item = Point>> HullExt for I {}
+
+/// Builds a hull from a stream of points, the current hull is updated on every [`push`] so
+/// [`finish`] only has to compute the bounds. By default the pushed points are not kept around,
+/// in that case `all` of the finished polygon only contains the hull vertices.
+///
+/// [`push`]: HullBuilder::push
+/// [`finish`]: HullBuilder::finish
+#[derive(Debug, Clone, Default)]
+pub struct HullBuilder {
+    // ccw, as long as there are fewer than 3 vertices all points seen so far are collinear
+    hull: Vec<Point>,
+    all: Option<Vec<Point>>,
+    pushed: usize,
+    // index of the first pushed point with a nan or infinite coordinate, it fails `finish`
+    non_finite: Option<usize>,
+}
+
+impl HullBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also keeps every pushed point so it ends up in `all` of the finished polygon.
+    pub fn keep_all(mut self) -> Self {
+        self.all = Some(vec![]);
+        self
+    }
+
+    /// Adds a point to the hull, points with a non-finite coordinate are skipped and make
+    /// [`finish`] fail.
+    ///
+    /// [`finish`]: HullBuilder::finish
+    pub fn push(&mut self, p: Point) {
+        self.pushed += 1;
+        if !p.x.is_finite() || !p.y.is_finite() {
+            self.non_finite.get_or_insert(self.pushed - 1);
+            return;
+        }
+
+        if let Some(all) = &mut self.all {
+            all.push(p);
+        }
+
+        if self.hull.len() < 2 {
+            if !self.hull.contains(&p) {
+                self.hull.push(p);
+            }
+            return;
+        }
+
+        if self.hull.len() == 2 {
+            let (a, b) = (self.hull[0], self.hull[1]);
+
+            match Orientation::calc(&a, &p, &b) {
+                Rightwards => self.hull.push(p),
+                Leftwards => self.hull = vec![b, a, p],
+                Collinear => {
+                    // still a segment, keep its two outermost points
+                    let dir = b - a;
+                    let t = dir.dot(p - a) / dir.dot(dir);
+                    if t < 0.0 {
+                        self.hull[0] = p;
+                    } else if t > 1.0 {
+                        self.hull[1] = p;
+                    }
+                }
+            }
+            return;
+        }
+
+        let n = self.hull.len();
+        let hull = &self.hull;
+        // p is strictly on the outer side of edge i
+        let visible = |i: usize| Orientation::calc(&hull[i], &p, &hull[(i + 1) % n]) == Leftwards;
+
+        let Some(edge) = (0..n).find(|&i| visible(i)) else {
+            return;
+        };
+
+        // the visible edges form one contiguous chain
+        let mut first = edge;
+        while visible((first + n - 1) % n) && (first + n - 1) % n != edge {
+            first = (first + n - 1) % n;
+        }
+        let mut last = edge;
+        while visible((last + 1) % n) && (last + 1) % n != first {
+            last = (last + 1) % n;
+        }
+
+        // the vertices inside the visible chain are replaced by p
+        let removed = (last + n - first) % n;
+        let mut new_hull: Vec<Point> = (0..n - removed)
+            .map(|k| hull[(last + 1 + k) % n])
+            .collect();
+        new_hull.push(p);
+        self.hull = new_hull;
+    }
+
+    /// Fails like [`ConvexPoly::new`] if no points were pushed or one of them wasn't finite.
+    pub fn finish(self) -> Result<ConvexPoly, HullError> {
+        if let Some(index) = self.non_finite {
+            return Err(HullError::NonFinite { index });
+        }
+        if self.hull.is_empty() {
+            return Err(HullError::Empty);
+        }
+
+        let mut hull = self.hull;
+        rotate_to_lowest(&mut hull);
+
+        let all = self.all.unwrap_or_else(|| hull.clone());
+        Ok(ConvexPoly::from_hull(all, hull))
+    }
+}
+
+/// Peels the point set like an onion, the first layer is the convex hull of all points, the
+/// next one the hull of what remains after removing its vertices and so on. Once fewer than 3
+/// points are left they form the innermost layer. All coordinates have to be finite.
+pub fn onion_layers(points: &[Point]) -> Vec<Vec<Point>> {
+    peel_layers(points, &mut vec![0; points.len()])
+        .into_iter()
+        .map(|layer| layer.into_iter().map(|i| points[i]).collect())
+        .collect()
+}
+
+/// For every point the index of its layer in [`onion_layers`], 0 for the hull of all points.
+/// Points inside of more layers are deeper in the point set, duplicates of a vertex share its depth.
+pub fn convex_depth(points: &[Point]) -> Vec<usize> {
+    let mut depth = vec![0; points.len()];
+    peel_layers(points, &mut depth);
+    depth
+}
+
+// the layers of `onion_layers` as indices into the points, every point removed with a layer gets
+// the index of that layer written to `depth`
+fn peel_layers(points: &[Point], depth: &mut [usize]) -> Vec<Vec<usize>> {
+    let mut layers = vec![];
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+
+    while remaining.len() >= 3 {
+        let hull = graham_scan_by(&mut remaining.clone(), |&i| points[i], |_| {});
+
+        // duplicates of a vertex are skipped by the scan, they get removed together with it
+        let mut sorted: Vec<Point> = hull.iter().map(|&i| points[i]).collect();
+        sorted.sort_by(cmp_points_xy);
+        remaining.retain(|&i| {
+            let on_hull = sorted.binary_search_by(|h| cmp_points_xy(h, &points[i])).is_ok();
+            if on_hull {
+                depth[i] = layers.len();
+            }
+            !on_hull
+        });
+
+        layers.push(hull);
+    }
+
+    if !remaining.is_empty() {
+        for &i in &remaining {
+            depth[i] = layers.len();
+        }
+        layers.push(remaining);
+    }
+
+    layers
+}
+
+/// Drops the points which lie strictly inside the quadrilateral of the lowest, rightmost, highest
+/// and leftmost point, none of those can be on the hull. For evenly spread points this removes
+/// most of them in linear time, the remaining points are in input order.
+pub fn akl_toussaint_filter(points: &[Point]) -> Vec<Point> {
+    let Some(first) = points.first() else {
+        return vec![];
+    };
+
+    let extreme = |better: fn(&Point, &Point) -> bool| {
+        points.iter().fold(*first, |best, p| if better(p, &best) { *p } else { best })
+    };
+    // ccw like the hull
+    let quad = [
+        extreme(|p, best| p.y < best.y),
+        extreme(|p, best| p.x > best.x),
+        extreme(|p, best| p.y > best.y),
+        extreme(|p, best| p.x < best.x),
+    ];
+
+    // points on an edge of the quadrilateral can still be on a hull edge, and if two of its corners
+    // coincide nothing is strictly inside
+    points.iter()
+        .filter(|p| !(0..4).all(|i| Orientation::calc(&quad[i], p, &quad[(i + 1) % 4]) == Rightwards))
+        .copied()
+        .collect()
+}
+
+/// Polygonal approximation of the convex hull of the disks around `centers` with the given `radii`,
+/// as a ccw list of vertices. Every circle is replaced by a regular polygon with `resolution`
+/// vertices which circumscribes it, so the result always contains all disks. With all radii 0
+/// this is just the hull of the centers. All coordinates have to be finite and there has to be
+/// one radius per center.
+pub fn hull_of_disks(centers: &[Point], radii: &[f64], resolution: usize) -> Vec<Point> {
+    assert_eq!(centers.len(), radii.len(), "every disk needs a center and a radius");
+
+    if centers.is_empty() {
+        return vec![];
+    }
+
+    let resolution = resolution.max(3);
+    let step = 2.0 * f64::PI() / resolution as f64;
+    // the vertices of the circumscribed polygon are farther out than the circle
+    let scale = 1.0 / (step / 2.0).cos();
+
+    let mut points: Vec<Point> = centers.iter()
+        .zip(radii)
+        .flat_map(|(c, r)| {
+            (0..resolution).map(move |k| {
+                let (sin, cos) = (k as f64 * step).sin_cos();
+                c + Point::new(cos, sin) * (r * scale)
+            })
+        })
+        .collect();
+
+    graham_scan(&mut points, |_| {})
+}
+
+/// Whether every point is a corner of the convex hull, i.e. no point lies inside of the hull or on
+/// an edge between two others. Near duplicates are merged first, up to 2 points are always in
+/// convex position and non-finite coordinates never are.
+pub fn is_convex_position(points: &[Point]) -> bool {
+    if points.iter().any(|p| !p.x.is_finite() || !p.y.is_finite()) {
+        return false;
+    }
+
+    if points.len() < 3 {
+        return true;
+    }
+
+    let unique = dedup_points(points.to_vec(), suggested_epsilon(points));
+    if unique.len() < 3 {
+        return true;
+    }
+
+    let count = unique.len();
+    let hull = ConvexPoly::from_valid(unique).hull;
+    let corners = (0..hull.len()).filter(|&i| is_corner(&hull, i)).count();
+
+    corners == count
+}
+
+/// Whether the polygon with the vertices in the given order is convex, in either orientation. It
+/// has to turn the same way at every vertex and go around only once, collinear vertices are fine
+/// but repeating a vertex right after itself is not. Like [`ConvexPoly::from_hull_checked`] a
+/// polygon without area, i.e. a point or a segment walked back and forth, counts as convex.
+pub fn is_convex(polygon: &[Point]) -> bool {
+    if polygon.is_empty() {
+        return false;
+    }
+
+    first_non_convex(polygon, Leftwards).is_none() || first_non_convex(polygon, Rightwards).is_none()
+}
+
+/// The lower chain of the convex hull from the leftmost to the rightmost point, i.e. the convex
+/// function below all points. Collinear points are dropped, both ends are shared with
+/// [`upper_hull`]: the lowest of the leftmost points and the highest of the rightmost ones.
+pub fn lower_hull(points: &[Point]) -> Vec<Point> {
+    monotone_half(sorted_unique(points).iter())
+}
+
+/// The upper chain of the convex hull, like [`lower_hull`] it goes from the leftmost to the
+/// rightmost point so both chains can be walked side by side.
+pub fn upper_hull(points: &[Point]) -> Vec<Point> {
+    let mut chain = monotone_half(sorted_unique(points).iter().rev());
+    chain.reverse();
+    chain
+}
+
+/// How the containment tests treat points which lie exactly on the hull boundary.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Inclusion {
+    /// Points on the boundary are inside.
+    Inclusive,
+    /// Only points strictly in the interior are inside.
+    Exclusive,
+}
+
+impl Inclusion {
+    // whether p can still be inside given its orientation relative to one ccw hull edge
+    fn accepts(self, orientation: Orientation) -> bool {
+        match self {
+            Inclusion::Inclusive => orientation != Leftwards,
+            Inclusion::Exclusive => orientation == Rightwards,
+        }
+    }
+}
+
+/// Tests `p` against every edge of the hull in O(n). With [`Inclusion::Inclusive`] points on an
+/// edge are inside, with [`Inclusion::Exclusive`] they are outside, [`is_point_in_polygon_fast`]
+/// gives the same answer in both modes.
+pub fn is_point_in_polygon(poly: &ConvexPoly, p: &Point, boundary: Inclusion) -> bool {
+    if !poly.bounding_box().contains(p) {
+        return false;
+    }
+
+    accepted_by_every_edge(&poly.hull, p, boundary)
+}
+
+fn accepted_by_every_edge(hull: &[Point], p: &Point, boundary: Inclusion) -> bool {
+    for i in 0..hull.len() {
+        let s = hull.get(i).unwrap();
+        let e = hull.get((i + 1) % hull.len()).unwrap();
+        if !boundary.accepts(Orientation::calc(s, p, e)) {
+            return false;
+        }
+    }
+
+    true
+}
+
+// finds the index in `low..=high` whose angle around `center` is nearest to `search_angle`,
+// the points have to be sorted ccw so their wrapped angles are ascending
+pub fn binary_search_angles(points: &[Point], low: usize, high: usize, center: &Point, search_angle: f64, offset_angle: f64) -> usize {
+    assert!(low <= high && high < points.len());
+
+    binary_search_wrapped(low, high, search_angle, |i| wrapped_angle_sub(angle(&(points[i] - center)), offset_angle))
+}
+
+// `binary_search_angles` over already computed wrapped angles
+fn binary_search_wrapped(mut low: usize, mut high: usize, search_angle: f64, wrapped_angle: impl Fn(usize) -> f64) -> usize {
+    // the search angle always stays between low and high, and as low < middle < high
+    // whenever high - low >= 2 the range shrinks in every iteration
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+
+        if wrapped_angle(middle) > search_angle {
+            high = middle;
+        } else {
+            low = middle;
+        }
+    }
+
+    let lowdiff = (wrapped_angle(low) - search_angle).abs();
+    let highdiff = (wrapped_angle(high) - search_angle).abs();
+
+    if lowdiff < highdiff { low } else { high }
+}
+
+// returns the hull vertex whose angle around the interior pivot is the closest to the angle of p
+// together with the angles of p and that vertex, both relative to the first hull vertex
+fn closest_node_by_angle(poly: &ConvexPoly, p: &Point) -> (usize, f64, f64) {
+    let center = poly.pivot();
+    let offset_angle = angle(&(poly.hull[0] - center));
+    let search_angle = wrapped_angle_sub(angle(&(p - center)), offset_angle);
+
+    closest_by_wrapped_angle(poly.hull.len(), search_angle, |i| {
+        wrapped_angle_sub(angle(&(poly.hull[i] - center)), offset_angle)
+    })
+}
+
+#[cfg(test)]
+thread_local! {
+    // number of angular searches on this thread, lets tests check which queries skip the search
+    static ANGULAR_SEARCHES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+// the search of `closest_node_by_angle` for `n` vertices with the given wrapped angles
+fn closest_by_wrapped_angle(n: usize, mut search_angle: f64, wrapped_angle: impl Fn(usize) -> f64) -> (usize, f64, f64) {
+    #[cfg(test)]
+    ANGULAR_SEARCHES.with(|count| count.set(count.get() + 1));
+
+    // should we use a negative or positive search angle => this is important as zero might be nearest point when search angle is almost 2 PI
+    let diff_zero = (search_angle - 2.0 * f64::PI()).abs();
+    let diff_last = (search_angle - wrapped_angle(n - 1)).abs();
+
+    if diff_zero < diff_last {
+        // yes negative would be better
+        search_angle -= 2.0 * f64::PI();
+    }
+
+    // binary search the two nodes whose angles are the nearest to `angle`
+    // this only works because hull is sorted ccw
+    let closest_node_by_angle = binary_search_wrapped(0, n - 1, search_angle, &wrapped_angle);
+    let closest_angle = wrapped_angle(closest_node_by_angle);
+
+    (closest_node_by_angle, search_angle, closest_angle)
+}
+
+/// Same result as [`is_point_in_polygon`] in O(log n), only the two edges next to the hull
+/// vertex which is the closest to `p` by angle around an interior pivot get tested. A degenerate
+/// hull has no interior to measure the angles in, it falls back to testing every edge.
+pub fn is_point_in_polygon_fast(poly: &ConvexPoly, p: &Point, boundary: Inclusion) -> bool {
+    // far away points don't need the angular search
+    if !poly.bounding_box().contains(p) {
+        return false;
+    }
+
+    if !poly.has_area() {
+        return accepted_by_every_edge(&poly.hull, p, boundary);
+    }
+
+    let (closest_node_by_angle, _, _) = closest_node_by_angle(poly, p);
+
+    let left = &poly.hull[(closest_node_by_angle + poly.hull.len() - 1) % poly.hull.len()];
+    let closest = &poly.hull[(closest_node_by_angle) % poly.hull.len()];
+    let right = &poly.hull[(closest_node_by_angle + 1) % poly.hull.len()];
+
+    boundary.accepts(Orientation::calc(left, p, closest)) &&
+        boundary.accepts(Orientation::calc(closest, p, right))
+}
+
+/// Everything [`is_point_in_polygon_fast`] computes before its search, i.e. the interior pivot and
+/// the angles of the hull vertices around it. Built once by [`ConvexPoly::query_context`] it
+/// answers the same queries without recomputing them, and it can be shared between threads.
+#[derive(Debug, Clone)]
+pub struct QueryContext {
+    hull: Vec<Point>,
+    bbox: Aabb,
+    // degenerate hulls are tested edge by edge instead
+    has_area: bool,
+    center: Point,
+    offset_angle: f64,
+    // angle of every hull vertex around the center relative to the first one, ascending
+    angles: Vec<f64>,
+}
+
+impl QueryContext {
+    /// Same result as [`is_point_in_polygon_fast`] for the polygon this was built from.
+    pub fn contains(&self, p: &Point, boundary: Inclusion) -> bool {
+        if !self.bbox.contains(p) {
+            return false;
+        }
+
+        if !self.has_area {
+            return accepted_by_every_edge(&self.hull, p, boundary);
+        }
+
+        let n = self.hull.len();
+        let search_angle = wrapped_angle_sub(angle(&(p - self.center)), self.offset_angle);
+        let (closest, _, _) = closest_by_wrapped_angle(n, search_angle, |i| self.angles[i]);
+
+        let left = &self.hull[(closest + n - 1) % n];
+        let right = &self.hull[(closest + 1) % n];
+
+        boundary.accepts(Orientation::calc(left, p, &self.hull[closest])) &&
+            boundary.accepts(Orientation::calc(&self.hull[closest], p, right))
+    }
+}
+
+/// Whether each point lies strictly inside, boundary points are outside.
+pub fn classify_points(poly: &ConvexPoly, points: &[Point]) -> Vec<bool> {
+    points.iter().map(|p| is_point_in_polygon_fast(poly, p, Inclusion::Exclusive)).collect()
+}
+
+/// Same as [`classify_points`] but the pivot and the vertex angles are set up once in a
+/// [`QueryContext`] for the whole batch, only points inside the bounding box reach the search.
+pub fn classify_points_with_prefilter(poly: &ConvexPoly, points: &[Point]) -> Vec<bool> {
+    let context = poly.query_context();
+    points.iter().map(|p| context.contains(p, Inclusion::Exclusive)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    use crate::rotation;
+
+    use super::*;
+
+    fn points(coords: &[(f64, f64)]) -> Vec<Point> {
+        coords.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    fn poly(coords: &[(f64, f64)]) -> ConvexPoly {
+        ConvexPoly::new(points(coords)).unwrap()
+    }
+
+    // a grid with half steps around the hull, so it hits vertices, edges, the inside and the outside
+    fn probes(poly: &ConvexPoly) -> Vec<Point> {
+        let bbox = poly.bounding_box();
+        let (x0, y0) = ((bbox.min.x - 1.0).floor(), (bbox.min.y - 1.0).floor());
+        let (columns, rows) = ((bbox.max.x - x0 + 2.0) * 2.0, (bbox.max.y - y0 + 2.0) * 2.0);
+
+        (0..rows as usize)
+            .flat_map(|r| (0..columns as usize).map(move |c| Point::new(x0 + c as f64 / 2.0, y0 + r as f64 / 2.0)))
+            .collect()
+    }
+
+    fn assert_fast_matches_naive(poly: &ConvexPoly) {
+        for p in probes(poly) {
+            for boundary in [Inclusion::Inclusive, Inclusion::Exclusive] {
+                assert_eq!(
+                    is_point_in_polygon_fast(poly, &p, boundary),
+                    is_point_in_polygon(poly, &p, boundary),
+                    "{p:?} {boundary:?} on {:?}", poly.hull(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fast_matches_naive_on_small_hulls() {
+        let triangle = poly(&[(0.0, 0.0), (4.0, 1.0), (1.0, 3.0)]);
+        let quad = poly(&[(1.0, 0.0), (4.0, 2.0), (2.0, 5.0), (0.0, 2.0)]);
+        let pentagon = poly(&[(2.0, 0.0), (4.0, 1.5), (3.0, 4.0), (1.0, 4.0), (0.0, 1.5)]);
+
+        for (hull, n) in [(triangle, 3), (quad, 4), (pentagon, 5)] {
+            assert_eq!(hull.hull().len(), n);
+            assert_fast_matches_naive(&hull);
+        }
+    }
+
+    #[test]
+    fn binary_search_angles_on_exact_vertex_angles() {
+        let center = Point::new(0.0, 0.0);
+        let triangle = [Point::new(1.0, 0.0), Point::new(-1.0, 1.0), Point::new(-1.0, -1.0)];
+        let square = [Point::new(1.0, 0.0), Point::new(0.0, 1.0), Point::new(-1.0, 0.0), Point::new(0.0, -1.0)];
+
+        for points in [&triangle[..], &square[..]] {
+            for (i, v) in points.iter().enumerate() {
+                assert_eq!(binary_search_angles(points, 0, points.len() - 1, &center, angle(v), 0.0), i);
+            }
+        }
+
+        assert_eq!(binary_search_angles(&square, 0, 3, &center, 0.3, 0.0), 0);
+        assert_eq!(binary_search_angles(&square, 0, 3, &center, 1.3, 0.0), 1);
+        assert_eq!(binary_search_angles(&square, 0, 3, &center, 3.5, 0.0), 2);
+    }
+
+    #[test]
+    fn fast_query_along_the_vertex_directions() {
+        for hull in [poly(&[(0.0, 0.0), (4.0, 1.0), (1.0, 3.0)]), poly(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)])] {
+            let pivot = hull.pivot();
+            for v in hull.hull() {
+                for scale in [0.5, 1.0, 2.0] {
+                    let p = pivot + (v - pivot) * scale;
+                    for boundary in [Inclusion::Inclusive, Inclusion::Exclusive] {
+                        assert_eq!(is_point_in_polygon_fast(&hull, &p, boundary), is_point_in_polygon(&hull, &p, boundary));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn all_keeps_every_input_point_sorted_around_the_lowest() {
+        let input = vec![
+            Point::new(2.0, 2.0), Point::new(4.0, 0.5), Point::new(1.0, 4.0),
+            Point::new(2.0, 2.0), Point::new(0.0, 1.0), Point::new(3.0, 3.0), Point::new(1.0, 0.0),
+        ];
+        let hull = ConvexPoly::new(input.clone()).unwrap();
+
+        assert_eq!(hull.all().len(), input.len());
+        assert_eq!(hull.all()[0], Point::new(1.0, 0.0));
+
+        let start = hull.all()[0];
+        let angles: Vec<f64> = hull.all()[1..].iter().map(|p| angle(&(p - start))).collect();
+        assert!(angles.windows(2).all(|w| w[0] <= w[1]), "{:?}", hull.all());
+    }
+
+    fn random_points(rng: &mut StdRng, n: usize, low: f64, high: f64) -> Vec<Point> {
+        (0..n).map(|_| Point::new(rng.gen_range(low..high), rng.gen_range(low..high))).collect()
+    }
+
+    #[test]
+    fn prefilter_matches_plain_classifier() {
+        let mut rng = StdRng::seed_from_u64(306);
+        let hull = ConvexPoly::new(random_points(&mut rng, 30, 0.0, 10.0)).unwrap();
+        let queries = random_points(&mut rng, 500, -2.0, 12.0);
+
+        assert_eq!(classify_points_with_prefilter(&hull, &queries), classify_points(&hull, &queries));
+    }
+
+    #[test]
+    fn prefilter_rejects_far_points() {
+        let mut rng = StdRng::seed_from_u64(306);
+        let hull = ConvexPoly::new(random_points(&mut rng, 30, 0.0, 10.0)).unwrap();
+        let mut queries = random_points(&mut rng, 500, 1000.0, 2000.0);
+        queries.push(hull.centroid());
+
+        let classified = classify_points_with_prefilter(&hull, &queries);
+        assert_eq!(classified.iter().filter(|&&inside| inside).count(), 1);
+        assert!(classified[500]);
+        assert_eq!(classified, classify_points(&hull, &queries));
+    }
+
+    #[test]
+    fn far_points_skip_the_angular_search() {
+        let mut rng = StdRng::seed_from_u64(333);
+        let hull = ConvexPoly::new(random_points(&mut rng, 30, 0.0, 10.0)).unwrap();
+        let far = random_points(&mut rng, 200, 1000.0, 2000.0);
+        let searches = || ANGULAR_SEARCHES.with(|count| count.get());
+
+        let before = searches();
+        for p in &far {
+            assert!(!is_point_in_polygon_fast(&hull, p, Inclusion::Exclusive));
+        }
+        assert_eq!(searches(), before);
+
+        assert!(is_point_in_polygon_fast(&hull, &hull.centroid(), Inclusion::Exclusive));
+        assert_eq!(searches(), before + 1);
+    }
+
+    #[test]
+    fn fast_matches_naive_on_degenerate_hulls() {
+        let diagonal = poly(&[(0.0, 0.0), (1.0, 1.0), (4.0, 4.0)]);
+        assert!(diagonal.is_degenerate());
+
+        let q = Point::new(0.0, 2.0);
+        for boundary in [Inclusion::Inclusive, Inclusion::Exclusive] {
+            assert!(!is_point_in_polygon_fast(&diagonal, &q, boundary));
+            assert!(!diagonal.query_context().contains(&q, boundary));
+        }
+        assert_eq!(classify_points(&diagonal, &[q, Point::new(2.0, 2.0)]), [false, false]);
+
+        for hull in [diagonal, poly(&[(0.0, 1.0), (3.0, 1.0)]), poly(&[(2.0, 2.0)])] {
+            assert_fast_matches_naive(&hull);
+        }
+    }
+
+    #[test]
+    fn fast_matches_naive_on_edge_points() {
+        let hull = poly(&[(0.0, 0.0), (6.0, 0.0), (8.0, 4.0), (2.0, 6.0)]);
+        let context = hull.query_context();
+        let n = hull.hull().len();
+
+        for i in 0..n {
+            let (a, b) = (hull.hull()[i], hull.hull()[(i + 1) % n]);
+            for t in [0.0, 0.25, 0.5, 0.75] {
+                let p = a + (b - a) * t;
+                assert!(is_point_in_polygon_fast(&hull, &p, Inclusion::Inclusive), "{p:?}");
+                assert!(!is_point_in_polygon_fast(&hull, &p, Inclusion::Exclusive), "{p:?}");
+                assert!(context.contains(&p, Inclusion::Inclusive) && !context.contains(&p, Inclusion::Exclusive));
+                assert!(is_point_in_polygon(&hull, &p, Inclusion::Inclusive) && !is_point_in_polygon(&hull, &p, Inclusion::Exclusive));
+            }
+        }
+    }
+
+    // near collinear point sets are where the orientation tests are the least robust
+    fn sample_near_collinear(rng: &mut StdRng, n: usize) -> Vec<Point> {
+        let start = Point::new(rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0));
+        let dir = Point::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+
+        (0..n)
+            .map(|_| start + dir * rng.gen_range(0.0..100.0) + Point::new(rng.gen_range(-1e-9..1e-9), rng.gen_range(-1e-9..1e-9)))
+            .collect()
+    }
+
+    fn fast_and_naive_disagree(points: &[Point], p: &Point) -> bool {
+        let Ok(poly) = ConvexPoly::new(points.to_vec()) else {
+            return false;
+        };
+        [Inclusion::Inclusive, Inclusion::Exclusive].into_iter()
+            .any(|boundary| is_point_in_polygon_fast(&poly, p, boundary) != is_point_in_polygon(&poly, p, boundary))
+    }
+
+    // greedily drops points as long as the query still disagrees
+    fn shrink_disagreement(mut points: Vec<Point>, p: &Point) -> Vec<Point> {
+        let mut i = 0;
+        while i < points.len() {
+            let mut smaller = points.clone();
+            smaller.remove(i);
+
+            if fast_and_naive_disagree(&smaller, p) {
+                points = smaller;
+            } else {
+                i += 1;
+            }
+        }
+        points
+    }
+
+    // integer coordinates give many collinear and degenerate hulls and queries exactly on the edges
+    #[test]
+    fn fast_matches_naive_on_random_hulls() {
+        let mut rng = StdRng::seed_from_u64(309);
+
+        for round in 0..1500 {
+            let n = rng.gen_range(1..30);
+            let (points, queries) = match round % 4 {
+                0 => (random_points(&mut rng, n, 0.0, 100.0), random_points(&mut rng, 20, -10.0, 110.0)),
+                1 => (sample_near_collinear(&mut rng, n), random_points(&mut rng, 20, -10.0, 110.0)),
+                2 => {
+                    // exactly collinear integer points, their hull has no area
+                    let start = Point::new(rng.gen_range(0..4) as f64, rng.gen_range(0..4) as f64);
+                    let dir = Point::new(rng.gen_range(1..3) as f64, rng.gen_range(-2..3) as f64);
+                    let line = (0..n.min(6)).map(|_| start + dir * rng.gen_range(0..4) as f64).collect();
+                    let probes = (0..40).map(|_| Point::new(rng.gen_range(-1..12) as f64, rng.gen_range(-8..12) as f64)).collect();
+                    (line, probes)
+                }
+                _ => {
+                    let grid = |rng: &mut StdRng, k| -> Vec<Point> {
+                        (0..k).map(|_| Point::new(rng.gen_range(0..6) as f64, rng.gen_range(0..6) as f64)).collect()
+                    };
+                    let n = rng.gen_range(1..6);
+                    (grid(&mut rng, n), grid(&mut rng, 40))
+                }
+            };
+
+            for q in &queries {
+                if fast_and_naive_disagree(&points, q) {
+                    let minimal = shrink_disagreement(points.clone(), q);
+                    panic!("fast and naive disagree in round {round} for {q:?} on {minimal:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn remove_interior_point_keeps_the_hull() {
+        let mut hull = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0), (1.0, 2.0), (3.0, 1.0)]);
+        let before = hull.hull().to_vec();
+
+        assert!(hull.remove_point(&Point::new(1.0, 2.0)));
+        assert_eq!(hull.hull(), before);
+        assert_eq!(hull.all().len(), 5);
+        assert!(!hull.remove_point(&Point::new(1.0, 2.0)));
+    }
+
+    #[test]
+    fn remove_hull_vertex_repairs_the_hull() {
+        let coords = [(0.0, 0.0), (4.0, 0.0), (5.0, 3.0), (4.0, 4.0), (0.0, 4.0), (3.0, 2.0), (2.0, 1.0)];
+        let mut hull = poly(&coords);
+
+        assert!(hull.remove_point(&Point::new(5.0, 3.0)));
+        let rebuilt = poly(&coords.iter().copied().filter(|&c| c != (5.0, 3.0)).collect::<Vec<_>>());
+        assert_eq!(hull.hull(), rebuilt.hull());
+        assert_eq!(hull.bounding_box(), rebuilt.bounding_box());
+    }
+
+    #[test]
+    fn remove_last_point_is_refused() {
+        let mut hull = poly(&[(1.0, 2.0)]);
+
+        assert!(!hull.remove_point(&Point::new(1.0, 2.0)));
+        assert_eq!(hull.all(), [Point::new(1.0, 2.0)]);
+        assert_eq!(hull.hull(), [Point::new(1.0, 2.0)]);
+    }
+
+    #[test]
+    fn streamed_hull_matches_new() {
+        let mut rng = StdRng::seed_from_u64(314);
+        let points = random_points(&mut rng, 1000, -50.0, 50.0);
+
+        let mut builder = HullBuilder::new().keep_all();
+        for p in &points {
+            builder.push(*p);
+        }
+        let streamed = builder.finish().unwrap();
+        let expected = ConvexPoly::new(points.clone()).unwrap();
+
+        assert_eq!(streamed.hull(), expected.hull());
+        assert_eq!(streamed.bounding_box(), expected.bounding_box());
+        assert_eq!(streamed.all(), points);
+    }
+
+    #[test]
+    fn hull_builder_rejects_empty_and_non_finite_input() {
+        assert_eq!(HullBuilder::new().finish().unwrap_err(), HullError::Empty);
+
+        let mut builder = HullBuilder::new();
+        builder.push(Point::new(0.0, 0.0));
+        builder.push(Point::new(f64::NAN, 1.0));
+        builder.push(Point::new(1.0, f64::INFINITY));
+        assert_eq!(builder.finish().unwrap_err(), HullError::NonFinite { index: 1 });
+    }
+
+    #[test]
+    fn nearest_hull_edge_of_a_square() {
+        let square = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+
+        assert_eq!(square.nearest_hull_edge(&Point::new(2.0, -3.0)), (0, 1));
+        assert_eq!(square.nearest_hull_edge(&Point::new(7.0, 2.0)), (1, 2));
+        assert_eq!(square.nearest_hull_edge(&Point::new(2.0, 7.0)), (2, 3));
+        assert_eq!(square.nearest_hull_edge(&Point::new(-3.0, 2.0)), (3, 0));
+    }
+
+    #[test]
+    fn merge_matches_hull_of_all_points() {
+        let mut rng = StdRng::seed_from_u64(325);
+        for offset in [0.0, 5.0, 30.0] {
+            let a = random_points(&mut rng, 40, -10.0, 10.0);
+            let b: Vec<_> = random_points(&mut rng, 40, -10.0, 10.0)
+                .into_iter()
+                .map(|p| p + Point::new(offset, offset / 2.0))
+                .collect();
+
+            let (pa, pb) = (ConvexPoly::new(a.clone()).unwrap(), ConvexPoly::new(b.clone()).unwrap());
+            let merged = pa.merge(&pb);
+            let expected = ConvexPoly::new([a, b].concat()).unwrap();
+
+            assert_eq!(merged.hull(), expected.hull());
+            assert_eq!(merged.all().len(), expected.all().len());
+            assert_eq!(merged.bounding_box(), expected.bounding_box());
+        }
+    }
+
+    #[test]
+    fn non_finite_points_are_reported_with_their_index() {
+        let nan_x = vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0), Point::new(f64::NAN, 1.0)];
+        assert_eq!(ConvexPoly::new(nan_x).unwrap_err(), HullError::NonFinite { index: 2 });
+
+        let inf_y = vec![Point::new(0.0, 0.0), Point::new(1.0, f64::INFINITY), Point::new(0.0, 1.0)];
+        assert_eq!(ConvexPoly::new(inf_y).unwrap_err(), HullError::NonFinite { index: 1 });
+    }
+
+    #[test]
+    fn fraction_inside_of_half_the_points() {
+        let square = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        let points = [
+            Point::new(1.0, 1.0), Point::new(2.0, 3.0), Point::new(3.5, 0.5),
+            Point::new(5.0, 1.0), Point::new(-1.0, 2.0), Point::new(2.0, 9.0),
+        ];
+
+        assert_eq!(square.fraction_inside(&points), 0.5);
+        assert_eq!(square.fraction_inside(&[]), 0.0);
+    }
+
+    #[test]
+    fn coords_match_the_points() {
+        let square = poly(&[(0.0, 4.0), (4.0, 4.0), (2.0, 1.0), (4.0, 0.0), (0.0, 0.0)]);
+
+        assert_eq!(square.hull_coords(), vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+
+        let all = square.all_coords();
+        assert_eq!(all.len(), 5);
+        assert!(all.contains(&(2.0, 1.0)));
+        assert!(all.iter().zip(square.all()).all(|(&(x, y), p)| x == p.x && y == p.y));
+    }
+
+    #[test]
+    fn support_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(331);
+        let hull = ConvexPoly::new(random_points(&mut rng, 60, -10.0, 10.0)).unwrap();
+
+        for _ in 0..500 {
+            let angle = rng.gen_range(0.0..2.0 * std::f64::consts::PI);
+            let direction = Point::new(angle.cos(), angle.sin());
+
+            let best = hull.hull().iter().map(|p| p.dot(direction)).fold(f64::NEG_INFINITY, f64::max);
+            assert_eq!(hull.support(direction).dot(direction), best, "direction {direction:?}");
+        }
+    }
+
+    #[test]
+    fn tangent_vertices_of_a_square() {
+        let square = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+
+        // straight below only the bottom edge is visible, diagonally the bottom and the right one
+        assert_eq!(square.tangent_vertices(&Point::new(2.0, -3.0)), Some((0, 1)));
+        assert_eq!(square.tangent_vertices(&Point::new(6.0, -2.0)), Some((0, 2)));
+        assert_eq!(square.tangent_vertices(&Point::new(-2.0, 6.0)), Some((2, 0)));
+        assert_eq!(square.tangent_vertices(&Point::new(2.0, 2.0)), None);
+    }
+
+    // vertices of axis aligned squares centered at the origin with half sides 1, 2, ..., `n`
+    fn concentric_squares(n: usize) -> Vec<Point> {
+        (1..=n)
+            .flat_map(|r| {
+                let r = r as f64;
+                [Point::new(-r, -r), Point::new(r, -r), Point::new(r, r), Point::new(-r, r)]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn onion_layers_of_concentric_squares() {
+        let layers = onion_layers(&concentric_squares(3));
+
+        assert_eq!(layers.len(), 3);
+        for (layer, r) in layers.iter().zip([3.0, 2.0, 1.0]) {
+            assert_eq!(layer.len(), 4);
+            assert!(layer.iter().all(|p| p.x.abs() == r && p.y.abs() == r));
+        }
+    }
+
+    #[test]
+    fn fan_segments_from_the_centroid_stay_inside() {
+        let mut rng = StdRng::seed_from_u64(337);
+        for n in [3, 5, 20, 200] {
+            let hull = ConvexPoly::new(random_points(&mut rng, n, -10.0, 10.0)).unwrap();
+            let center = hull.centroid();
+
+            // the vertex itself is on the boundary, `center + (v - center)` could round past it
+            for v in hull.hull() {
+                for step in 0..20 {
+                    let p = center + (v - center) * (step as f64 / 20.0);
+                    assert!(is_point_in_polygon(&hull, &p, Inclusion::Inclusive), "{p:?} on the way to {v:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn outliers_beyond_finds_the_planted_points() {
+        let mut rng = StdRng::seed_from_u64(339);
+        let square = poly(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+
+        let mut points = random_points(&mut rng, 100, 0.0, 10.0);
+        // just outside but within the distance, then the planted outliers
+        points.push(Point::new(10.5, 5.0));
+        points.insert(17, Point::new(15.0, 5.0));
+        points.insert(42, Point::new(-3.0, -3.0));
+        points.push(Point::new(5.0, 12.5));
+
+        assert_eq!(square.outliers_beyond(&points, 1.0), vec![17, 42, 103]);
+    }
+
+    #[test]
+    fn bbox_fill_ratio_of_rectangle_and_triangle() {
+        assert_eq!(poly(&[(1.0, 1.0), (5.0, 1.0), (5.0, 3.0), (1.0, 3.0)]).bbox_fill_ratio(), 1.0);
+        assert!((poly(&[(0.0, 0.0), (4.0, 0.0), (1.0, 3.0)]).bbox_fill_ratio() - 0.5).abs() < 1e-12);
+        assert_eq!(poly(&[(0.0, 0.0), (4.0, 0.0)]).bbox_fill_ratio(), 0.0);
+    }
+
+    #[test]
+    fn transform_translates_rotates_and_reflects() {
+        let triangle = poly(&[(0.0, 0.0), (2.0, 0.0), (0.0, 1.0)]);
+
+        let moved = triangle.transform(&translation(3.0, 4.0));
+        assert_eq!(moved.hull(), poly(&[(3.0, 4.0), (5.0, 4.0), (3.0, 5.0)]).hull());
+
+        let rotated = triangle.transform(&rotation(f64::FRAC_PI_2()));
+        assert_eq!(rotated, poly(&[(0.0, 0.0), (0.0, 2.0), (-1.0, 0.0)]));
+
+        // the reflection flips the winding, transform has to turn it back to ccw
+        let reflected = triangle.transform(&reflection(Point::new(1.0, 0.0)));
+        assert_eq!(reflected, poly(&[(0.0, 0.0), (2.0, 0.0), (0.0, -1.0)]));
+        assert_eq!(reflected.hull()[0], Point::new(0.0, -1.0));
+
+        for t in [&moved, &rotated, &reflected] {
+            assert!((t.area() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn nearest_vertex_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(344);
+        for _ in 0..20 {
+            let hull = ConvexPoly::new(random_points(&mut rng, 40, -10.0, 10.0)).unwrap();
+
+            for p in random_points(&mut rng, 50, -15.0, 15.0) {
+                let (index, distance) = hull.nearest_vertex(&p);
+                let best = hull.hull().iter().map(|h| (h - p).magnitude()).fold(f64::INFINITY, f64::min);
+
+                assert_eq!(distance, best, "{p:?}");
+                assert_eq!((hull.hull()[index] - p).magnitude(), best);
+            }
+        }
+
+        // every corner is a local minimum seen from near the center, the search must not stop early
+        let rectangle = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (0.0, 2.0)]);
+        assert_eq!(rectangle.nearest_vertex(&Point::new(2.1, 1.1)).0, 2);
+    }
+
+    #[test]
+    fn svg_path_has_one_line_per_vertex() {
+        let pentagon = poly(&[(0.0, 0.0), (2.0, 0.0), (3.0, 1.5), (1.0, 3.0), (-1.0, 1.5)]);
+
+        let d = pentagon.to_svg_path(None);
+        assert!(d.starts_with("M 0,0"));
+        assert!(d.ends_with('Z'));
+        assert_eq!(d.matches('L').count(), pentagon.hull().len());
+        assert!(d.contains("L 3,1.5"));
+
+        assert!(pentagon.to_svg_path(Some(10.0)).contains("L 3,8.5"));
+    }
+
+    #[test]
+    fn fan_triangles_match_triangulate_fan() {
+        let mut rng = StdRng::seed_from_u64(346);
+        let hull = ConvexPoly::new(random_points(&mut rng, 30, -5.0, 5.0)).unwrap();
+
+        let triangles: Vec<_> = hull.fan_triangles().collect();
+        assert_eq!(triangles, hull.triangulate_fan());
+        assert_eq!(triangles.len(), hull.hull().len() - 2);
+        assert_eq!(poly(&[(0.0, 0.0), (1.0, 0.0)]).fan_triangles().count(), 0);
+    }
+
+    #[test]
+    fn extremes_lie_on_the_bounding_box() {
+        let mut rng = StdRng::seed_from_u64(347);
+        for _ in 0..20 {
+            let hull = ConvexPoly::new(random_points(&mut rng, 25, -10.0, 10.0)).unwrap();
+            let bbox = hull.bounding_box();
+            let [top, bottom, right, left] = hull.extremes();
+
+            assert_eq!(top.y, bbox.max.y);
+            assert_eq!(bottom.y, bbox.min.y);
+            assert_eq!(right.x, bbox.max.x);
+            assert_eq!(left.x, bbox.min.x);
+        }
+    }
+
+    #[test]
+    fn antipodal_pairs_of_a_rectangle() {
+        let rectangle = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (0.0, 2.0)]);
+
+        // the horizontal calipers pair the bottom with the top edge, the vertical ones the left with
+        // the right edge, together that's every pair of corners
+        let mut pairs = rectangle.antipodal_pairs();
+        pairs.sort();
+        assert_eq!(pairs, vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn is_degenerate_cases() {
+        assert!(!poly(&[(0.0, 0.0), (3.0, 0.0), (1.0, 2.0)]).is_degenerate());
+        assert!(poly(&[(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (5.0, 5.0)]).is_degenerate());
+        assert!(poly(&[(0.0, 0.0), (1.0, 2.0)]).is_degenerate());
+        assert!(poly(&[(1.0, 2.0)]).is_degenerate());
+    }
+
+    #[test]
+    fn builder_merges_points_within_its_epsilon() {
+        let builder = ConvexPoly::builder()
+            .points(points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]))
+            .point(4.05, 4.0)
+            .point(0.0, 0.03);
+
+        let exact = builder.clone().build().unwrap();
+        assert_eq!(exact.all().len(), 6);
+
+        let merged = builder.epsilon(0.1).build().unwrap();
+        assert_eq!(merged.all().len(), 4);
+        assert_eq!(merged.hull(), points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]));
+    }
+
+    #[test]
+    fn convex_position_with_and_without_an_interior_point() {
+        let mut pentagon = points(&[(0.0, 0.0), (2.0, 0.0), (3.0, 1.5), (1.0, 3.0), (-1.0, 1.5)]);
+        assert!(is_convex_position(&pentagon));
+
+        pentagon.push(Point::new(1.0, 1.0));
+        assert!(!is_convex_position(&pentagon));
+    }
+
+    #[test]
+    fn largest_inscribed_circle_of_a_square() {
+        let square = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        let (center, radius) = square.largest_inscribed_circle();
+
+        assert!((center - Point::new(2.0, 2.0)).magnitude() < 1e-6, "{center:?}");
+        assert!((radius - 2.0).abs() < 1e-6, "{radius}");
+    }
+
+    #[test]
+    fn query_context_matches_fast_test() {
+        let mut rng = StdRng::seed_from_u64(353);
+        for _ in 0..20 {
+            let hull = ConvexPoly::new(random_points(&mut rng, 30, 0.0, 10.0)).unwrap();
+            let ctx = hull.query_context();
+
+            for p in random_points(&mut rng, 200, -2.0, 12.0).iter().chain(hull.hull()) {
+                for boundary in [Inclusion::Inclusive, Inclusion::Exclusive] {
+                    assert_eq!(ctx.contains(p, boundary), is_point_in_polygon_fast(&hull, p, boundary), "{p:?} {boundary:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stats_count_every_push_and_pop() {
+        // sorted around (0, 0) the interior point (2, 1) comes before (4, 4) and gets popped by it
+        let (hull, stats) = ConvexPoly::new_with_stats(points(&[(0.0, 4.0), (4.0, 4.0), (2.0, 1.0), (4.0, 0.0), (0.0, 0.0)])).unwrap();
+
+        assert_eq!(hull.hull().len(), 4);
+        assert_eq!(stats, HullStats { pops: 1, pushes: 5, comparisons: 4 });
+        assert_eq!(stats.pushes - stats.pops, hull.hull().len());
+    }
+
+    #[test]
+    fn offset_grows_and_collapses_a_square() {
+        let square = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+
+        let grown = square.offset(1.0);
+        assert!((grown.area() - 36.0).abs() < 1e-9);
+        assert_eq!(grown, poly(&[(-1.0, -1.0), (5.0, -1.0), (5.0, 5.0), (-1.0, 5.0)]));
+
+        let shrunk = square.offset(-1.0);
+        assert!((shrunk.area() - 4.0).abs() < 1e-9);
+
+        let collapsed = square.offset(-10.0);
+        assert!(collapsed.is_degenerate());
+        assert_eq!(collapsed.area(), 0.0);
+        assert!(collapsed.hull().iter().all(|p| (p - Point::new(2.0, 2.0)).magnitude() < 1e-6));
+    }
+
+    #[test]
+    fn cw_vertices_reverse_the_ccw_ones() {
+        let pentagon = poly(&[(0.0, 1.0), (2.0, 0.0), (3.0, 1.5), (1.0, 3.0), (-1.0, 1.5)]);
+        let ccw: Vec<_> = pentagon.vertices_ccw().collect();
+        let mut cw: Vec<_> = pentagon.vertices_cw().collect();
+
+        // both start at the leftmost vertex, not at the stored lowest one
+        assert_eq!(ccw[0], Point::new(-1.0, 1.5));
+        assert_eq!(cw[0], ccw[0]);
+
+        cw[1..].reverse();
+        assert_eq!(cw, ccw);
+    }
+
+    #[test]
+    fn differently_built_hulls_are_equal() {
+        // interior points and a vertex in the middle of the bottom edge don't change the shape
+        let scanned = poly(&[(1.0, 1.0), (0.0, 4.0), (2.0, 0.0), (4.0, 4.0), (3.0, 2.0), (0.0, 0.0), (4.0, 0.0)]);
+        let checked = ConvexPoly::from_hull_checked(points(&[(4.0, 4.0), (0.0, 4.0), (0.0, 0.0), (4.0, 0.0)])).unwrap();
+        let streamed = {
+            let mut builder = HullBuilder::new();
+            for p in points(&[(0.0, 4.0), (4.0, 0.0), (4.0, 4.0), (0.0, 0.0)]) {
+                builder.push(p);
+            }
+            builder.finish().unwrap()
+        };
+
+        assert_eq!(scanned, checked);
+        assert_eq!(checked, streamed);
+        assert_ne!(scanned, poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 5.0), (0.0, 4.0)]));
+    }
+
+    #[test]
+    fn hausdorff_distance_of_identical_and_shifted_squares() {
+        let square = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        assert_eq!(square.hausdorff_distance(&square), 0.0);
+
+        let shifted = square.transform(&translation(1.5, 0.0));
+        assert!((square.hausdorff_distance(&shifted) - 1.5).abs() < 1e-9);
+        assert!((shifted.hausdorff_distance(&square) - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sample_interior_fills_both_halves_equally() {
+        // symmetric about x = 2 and y = 1 but the fan triangles from (0, 0) have different areas
+        let hexagon = poly(&[(0.0, 0.0), (4.0, 0.0), (5.0, 1.0), (4.0, 2.0), (0.0, 2.0), (-1.0, 1.0)]);
+        let mut rng = StdRng::seed_from_u64(361);
+        let n = 20_000;
+        let samples = hexagon.sample_interior(&mut rng, n);
+
+        assert_eq!(samples.len(), n);
+        assert!(samples.iter().all(|p| is_point_in_polygon(&hexagon, p, Inclusion::Inclusive)));
+
+        // the standard deviation of either share is about 0.0035
+        let left = samples.iter().filter(|p| p.x < 2.0).count() as f64 / n as f64;
+        let bottom = samples.iter().filter(|p| p.y < 1.0).count() as f64 / n as f64;
+        assert!((left - 0.5).abs() < 0.02, "{left}");
+        assert!((bottom - 0.5).abs() < 0.02, "{bottom}");
+    }
+
+    #[test]
+    fn second_moments_of_a_rectangle() {
+        // width b = 4 along x and height h = 2 along y, away from the origin
+        let rectangle = poly(&[(3.0, 1.0), (7.0, 1.0), (7.0, 3.0), (3.0, 3.0)]);
+        let (ixx, iyy, ixy) = rectangle.second_moments();
+
+        assert!((ixx - 4.0 * 2.0_f64.powi(3) / 12.0).abs() < 1e-9);
+        assert!((iyy - 2.0 * 4.0_f64.powi(3) / 12.0).abs() < 1e-9);
+        assert!(ixy.abs() < 1e-9);
+    }
+
+    #[test]
+    fn triangle_hull_matches_the_three_orientation_check() {
+        let mut rng = StdRng::seed_from_u64(363);
+        for _ in 0..50 {
+            let triangle = ConvexPoly::new(random_points(&mut rng, 3, 0.0, 10.0)).unwrap();
+            let [a, b, c] = [triangle.hull()[0], triangle.hull()[1], triangle.hull()[2]];
+
+            for p in random_points(&mut rng, 200, -1.0, 11.0).iter().chain(triangle.hull()) {
+                for boundary in [Inclusion::Inclusive, Inclusion::Exclusive] {
+                    // what the triangle demo used to check inline, every edge has p on its inner side
+                    let inlined = boundary.accepts(Orientation::calc(&a, p, &b)) &&
+                        boundary.accepts(Orientation::calc(&b, p, &c)) &&
+                        boundary.accepts(Orientation::calc(&c, p, &a));
+
+                    assert_eq!(is_point_in_polygon_fast(&triangle, p, boundary), inlined, "{p:?} {boundary:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn contains_origin_before_and_after_translating() {
+        let straddling = poly(&[(-1.0, -2.0), (3.0, -1.0), (1.0, 2.0)]);
+        assert!(straddling.contains_origin());
+        assert!(!straddling.transform(&translation(5.0, 0.0)).contains_origin());
+
+        // on the boundary counts as inside
+        assert!(poly(&[(0.0, 0.0), (2.0, 0.0), (0.0, 2.0)]).contains_origin());
+    }
+
+    #[test]
+    fn from_hull_checked_accepts_convex_and_rejects_concave() {
+        let square = points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        assert_eq!(ConvexPoly::from_hull_checked(square.clone()).unwrap().hull(), square);
+
+        let dented = points(&[(0.0, 0.0), (4.0, 0.0), (2.0, 1.0), (4.0, 4.0), (0.0, 4.0)]);
+        assert_eq!(ConvexPoly::from_hull_checked(dented).unwrap_err(), HullError::NotConvex { index: 2 });
+
+        let cw: Vec<_> = square.iter().rev().copied().collect();
+        assert!(matches!(ConvexPoly::from_hull_checked(cw), Err(HullError::NotConvex { .. })));
+        assert_eq!(ConvexPoly::from_hull_checked(vec![]).unwrap_err(), HullError::Empty);
+    }
+
+    #[test]
+    fn width_along_the_axes_matches_the_bounds() {
+        let mut rng = StdRng::seed_from_u64(368);
+        for _ in 0..20 {
+            let hull = ConvexPoly::new(random_points(&mut rng, 25, -10.0, 10.0)).unwrap();
+            let bbox = hull.bounding_box();
+
+            assert_eq!(hull.width_in_direction(Point::new(1.0, 0.0)), bbox.max.x - bbox.min.x);
+            assert_eq!(hull.width_in_direction(Point::new(0.0, -3.0)), bbox.max.y - bbox.min.y);
+        }
+    }
+
+    // the hull corners sorted by x and y by brute force in O(n³), slow but simple enough to serve as
+    // the reference for the real algorithms. p is a corner if there is another point b such that every
+    // other point lies left of the line from p to b or on the ray from p through b.
+    fn reference_hull(points: &[Point]) -> Vec<Point> {
+        let mut corners: Vec<Point> = points.iter()
+            .copied()
+            .filter(|p| {
+                let others: Vec<&Point> = points.iter().filter(|q| *q != p).collect();
+                others.is_empty() || others.iter().any(|b| others.iter().all(|q| match Orientation::calc(p, q, b) {
+                    Rightwards => true,
+                    Collinear => (*q - p).dot(*b - p) > 0.0,
+                    Leftwards => false,
+                }))
+            })
+            .collect();
+
+        corners.sort_by(cmp_points_xy);
+        corners.dedup();
+        corners
+    }
+
+    // the corners of every hull construction sorted like `reference_hull`, in the order graham scan,
+    // monotone chain through `merge`, incremental `HullBuilder` and the lower and upper chains
+    fn hulls_by_every_algorithm(points: &[Point]) -> [Vec<Point>; 4] {
+        let sorted = |mut corners: Vec<Point>| {
+            corners.sort_by(cmp_points_xy);
+            corners.dedup();
+            corners
+        };
+
+        let (a, b) = points.split_at(points.len() / 2);
+        let merged = if a.is_empty() {
+            ConvexPoly::new(b.to_vec()).unwrap()
+        } else {
+            ConvexPoly::new(a.to_vec()).unwrap().merge(&ConvexPoly::new(b.to_vec()).unwrap())
+        };
+
+        let mut builder = HullBuilder::new();
+        for p in points {
+            builder.push(*p);
+        }
+
+        [
+            sorted(ConvexPoly::new(points.to_vec()).unwrap().canonical()),
+            sorted(merged.canonical()),
+            sorted(builder.finish().unwrap().canonical()),
+            sorted([lower_hull(points), upper_hull(points)].concat()),
+        ]
+    }
+
+    #[test]
+    fn hulls_match_the_reference() {
+        let mut rng = StdRng::seed_from_u64(369);
+
+        for round in 0..600 {
+            let n = rng.gen_range(2..40);
+            // small integer grids put many points on the hull edges, which is where scans tend to break
+            let points = match round % 3 {
+                0 => (0..n).map(|_| Point::new(rng.gen_range(0..8) as f64, rng.gen_range(0..8) as f64)).collect(),
+                1 => random_points(&mut rng, n, 0.0, 100.0),
+                _ => sample_near_collinear(&mut rng, n),
+            };
+
+            let reference = reference_hull(&points);
+            for (algorithm, corners) in hulls_by_every_algorithm(&points).iter().enumerate() {
+                assert_eq!(corners, &reference, "algorithm {algorithm} differs in round {round} on {points:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn collinear_boundary_points_match_the_reference() {
+        // every integer point on the boundary of a 10 by 6 rectangle plus a few inside, shuffled
+        let mut points: Vec<Point> = (0..=10)
+            .flat_map(|x| [Point::new(x as f64, 0.0), Point::new(x as f64, 6.0)])
+            .chain((1..6).flat_map(|y| [Point::new(0.0, y as f64), Point::new(10.0, y as f64)]))
+            .chain([Point::new(5.0, 3.0), Point::new(1.0, 1.0)])
+            .collect();
+        points.shuffle(&mut StdRng::seed_from_u64(369));
+
+        let reference = reference_hull(&points);
+        assert_eq!(reference, vec![Point::new(0.0, 0.0), Point::new(0.0, 6.0), Point::new(10.0, 0.0), Point::new(10.0, 6.0)]);
+        for corners in hulls_by_every_algorithm(&points) {
+            assert_eq!(corners, reference);
+        }
+
+        // all points on one line only keep the two ends
+        let line: Vec<Point> = (0..20).map(|i| Point::new(i as f64 * 0.5, i as f64 * 1.5)).rev().collect();
+        let reference = reference_hull(&line);
+        assert_eq!(reference, vec![Point::new(0.0, 0.0), Point::new(9.5, 28.5)]);
+        for corners in hulls_by_every_algorithm(&line) {
+            assert_eq!(corners, reference);
+        }
+    }
+
+    #[test]
+    fn fast_matches_naive_when_the_first_vertices_are_collinear() {
+        let flat_start = poly(&[(0.0, 0.0), (2.0, 0.0), (4.0, 0.0), (6.0, 0.0), (6.0, 3.0), (3.0, 5.0), (0.0, 3.0)]);
+        assert_eq!(&flat_start.hull()[..3], points(&[(0.0, 0.0), (2.0, 0.0), (4.0, 0.0)]));
+
+        assert_fast_matches_naive(&flat_start);
+        assert_fast_matches_naive(&poly(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (1.0, 1.0)]));
+    }
+
+    #[test]
+    fn collect_hull_from_an_iterator() {
+        let coords = [(0.0, 0.0), (4.0, 0.0), (2.0, 1.0), (4.0, 4.0), (0.0, 4.0)];
+        let collected = points(&coords).into_iter().collect_hull().unwrap();
+
+        assert_eq!(collected, poly(&coords));
+        assert_eq!(collected.all().len(), 5);
+        assert_eq!(std::iter::empty().collect_hull().unwrap_err(), HullError::Empty);
+    }
+
+    #[test]
+    fn max_angular_gap_of_a_pentagon_and_a_flat_sided_square() {
+        let pentagon = ConvexPoly::new(
+            (0..5)
+                .map(|k| {
+                    let a = 0.3 + 2.0 * f64::PI() * k as f64 / 5.0;
+                    Point::new(1.0 + 3.0 * a.cos(), 2.0 + 3.0 * a.sin())
+                })
+                .collect(),
+        ).unwrap();
+        assert!((pentagon.max_angular_gap() - 2.0 * f64::PI() / 5.0).abs() < 1e-9);
+
+        // the vertex in the middle of the bottom edge splits that gap, the other sides still span pi/2
+        let square = poly(&[(0.0, 0.0), (2.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        assert_eq!(square.hull().len(), 5);
+        assert!((square.max_angular_gap() - f64::FRAC_PI_2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn points_on_the_boundary_in_both_modes() {
+        let hull = poly(&[(0.0, 0.0), (4.0, 0.0), (6.0, 3.0), (2.0, 5.0), (-1.0, 2.0)]);
+        let on_boundary = points(&[(2.0, 0.0), (5.0, 1.5), (4.0, 4.0), (0.5, 3.5), (-0.5, 1.0), (0.0, 0.0), (6.0, 3.0)]);
+
+        for p in &on_boundary {
+            assert!(is_point_in_polygon(&hull, p, Inclusion::Inclusive), "{p:?}");
+            assert!(is_point_in_polygon_fast(&hull, p, Inclusion::Inclusive), "{p:?}");
+            assert!(!is_point_in_polygon(&hull, p, Inclusion::Exclusive), "{p:?}");
+            assert!(!is_point_in_polygon_fast(&hull, p, Inclusion::Exclusive), "{p:?}");
+        }
+    }
+
+    #[test]
+    fn diameter_indices_match_diameter() {
+        let mut rng = StdRng::seed_from_u64(376);
+        for _ in 0..30 {
+            let hull = ConvexPoly::new(random_points(&mut rng, 30, -10.0, 10.0)).unwrap();
+            let (i, j) = hull.diameter_indices();
+            let (a, b) = hull.diameter();
+
+            assert_eq!((hull.hull()[i], hull.hull()[j]), (a, b));
+
+            let farthest = hull.hull().iter()
+                .flat_map(|p| hull.hull().iter().map(move |q| (p - q).magnitude()))
+                .fold(0.0, f64::max);
+            assert_eq!((a - b).magnitude(), farthest);
+        }
+
+        assert_eq!(poly(&[(1.0, 1.0)]).diameter_indices(), (0, 0));
+    }
+
+    #[test]
+    fn is_convex_cases() {
+        let square = points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        assert!(is_convex(&square));
+        assert!(is_convex(&square.iter().rev().copied().collect::<Vec<_>>()));
+
+        assert!(!is_convex(&points(&[(0.0, 0.0), (4.0, 0.0), (2.0, 1.0), (4.0, 4.0), (0.0, 4.0)])));
+        assert!(is_convex(&points(&[(0.0, 0.0), (2.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)])));
+        assert!(!is_convex(&points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)])));
+    }
+
+    #[test]
+    fn outward_normals_of_a_square() {
+        let square = poly(&[(0.0, 0.0), (3.0, 0.0), (3.0, 3.0), (0.0, 3.0)]);
+
+        assert_eq!(square.edge_outward_normal(0), Point::new(0.0, -1.0));
+        assert_eq!(square.edge_outward_normal(1), Point::new(1.0, 0.0));
+        assert_eq!(square.edge_outward_normal(2), Point::new(0.0, 1.0));
+        assert_eq!(square.edge_outward_normal(3), Point::new(-1.0, 0.0));
+    }
+
+    #[test]
+    fn hull_of_disks_with_zero_radii_and_a_single_disk() {
+        let mut rng = StdRng::seed_from_u64(380);
+        let centers = random_points(&mut rng, 30, -10.0, 10.0);
+        let hull = hull_of_disks(&centers, &[0.0; 30], 16);
+        assert_eq!(ConvexPoly::from_hull_checked(hull).unwrap(), ConvexPoly::new(centers).unwrap());
+
+        // the octagon circumscribes the disk, its vertices are farther out and its edges touch it
+        let center = Point::new(1.0, -1.0);
+        let octagon = ConvexPoly::from_hull_checked(hull_of_disks(&[center], &[2.0], 8)).unwrap();
+        assert_eq!(octagon.hull().len(), 8);
+        for v in octagon.hull() {
+            assert!(((v - center).magnitude() - 2.0 / (f64::PI() / 8.0).cos()).abs() < 1e-9);
+        }
+        assert!((octagon.signed_distance(&center) + 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn akl_toussaint_keeps_the_hull() {
+        let mut rng = StdRng::seed_from_u64(382);
+        for n in [1, 3, 10, 100, 2000] {
+            let points = random_points(&mut rng, n, -10.0, 10.0);
+            let filtered = akl_toussaint_filter(&points);
+            let full = ConvexPoly::new(points.clone()).unwrap();
+
+            assert!(filtered.len() <= points.len());
+            assert!(full.hull().iter().all(|v| filtered.contains(v)));
+            assert_eq!(ConvexPoly::new_fast(points).unwrap().hull(), full.hull());
+        }
+
+        // in a square the quadrilateral covers about half of the area, so about half of the points go
+        let points = random_points(&mut rng, 2000, -10.0, 10.0);
+        assert!(akl_toussaint_filter(&points).len() < points.len() * 3 / 4);
+    }
+
+    #[test]
+    fn visible_edges_of_a_square() {
+        let square = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+
+        assert_eq!(square.visible_edges(&Point::new(2.0, -3.0)), vec![0]);
+        assert_eq!(square.visible_edges(&Point::new(6.0, -2.0)), vec![0, 1]);
+        // the chain wraps around the first vertex and is still listed ccw
+        assert_eq!(square.visible_edges(&Point::new(-2.0, -2.0)), vec![3, 0]);
+        // in line with an edge that edge isn't visible
+        assert_eq!(square.visible_edges(&Point::new(6.0, 0.0)), vec![1]);
+        assert!(square.visible_edges(&Point::new(2.0, 2.0)).is_empty());
+        assert!(square.visible_edges(&Point::new(4.0, 2.0)).is_empty());
+    }
+
+    #[test]
+    fn remove_collinear_vertices_keeps_the_corners() {
+        let mut square = poly(&[(0.0, 0.0), (2.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0), (0.0, 1.0)]);
+        assert_eq!(square.hull().len(), 6);
+
+        square.remove_collinear_vertices();
+        assert_eq!(square.hull(), points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]));
+        assert_eq!(square.all().len(), 6);
+    }
+
+    #[test]
+    fn hull_closed_repeats_the_first_vertex() {
+        let triangle = poly(&[(0.0, 0.0), (3.0, 0.0), (1.0, 2.0)]);
+        let closed = triangle.hull_closed();
+
+        assert_eq!(closed.len(), triangle.hull().len() + 1);
+        assert_eq!(closed.first(), closed.last());
+        assert_eq!(&closed[..3], triangle.hull());
+    }
+
+    #[test]
+    fn lower_and_upper_hull_of_a_parabola() {
+        // y = x^2 is convex, every point is on the lower chain and only the ends on the upper one
+        let parabola: Vec<Point> = (-3..=3).rev().map(|x| Point::new(x as f64, (x * x) as f64)).collect();
+        let mut by_x = parabola.clone();
+        by_x.reverse();
+
+        assert_eq!(lower_hull(&parabola), by_x);
+        assert_eq!(upper_hull(&parabola), points(&[(-3.0, 9.0), (3.0, 9.0)]));
+
+        let flipped: Vec<Point> = parabola.iter().map(|p| Point::new(p.x, -p.y)).collect();
+        assert_eq!(lower_hull(&flipped), points(&[(-3.0, -9.0), (3.0, -9.0)]));
+        assert_eq!(upper_hull(&flipped).len(), 7);
+    }
+
+    #[test]
+    fn aspect_ratio_and_principal_orientation_of_rectangles() {
+        let rect = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 1.0), (0.0, 1.0)]);
+        assert!((rect.aspect_ratio() - 4.0).abs() < 1e-12);
+        let orientation = rect.principal_orientation();
+        assert!(orientation.min(f64::PI() - orientation) < 1e-9, "{}", orientation);
+
+        // the bounding box of a rotated rectangle is square, but its orientation follows the rotation
+        let rotated = rect.transform(&rotation(f64::FRAC_PI_4()));
+        assert!((rotated.aspect_ratio() - 1.0).abs() < 1e-9);
+        let orientation = rotated.principal_orientation();
+        let expected = f64::FRAC_PI_4();
+        let diff = (orientation - expected).rem_euclid(f64::PI());
+        assert!(diff.min(f64::PI() - diff) < 1e-9, "{} != {}", orientation, expected);
+    }
+
+    #[test]
+    fn aspect_ratio_of_degenerate_hulls() {
+        assert!(poly(&[(2.0, 3.0)]).aspect_ratio().is_nan());
+        assert_eq!(poly(&[(0.0, 0.0), (4.0, 0.0)]).aspect_ratio(), f64::INFINITY);
+        assert_eq!(poly(&[(0.0, 0.0), (0.0, 4.0)]).aspect_ratio(), 0.0);
+    }
+
+    #[test]
+    fn minkowski_difference_contains_the_origin_only_when_overlapping() {
+        let square = poly(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        let triangle = poly(&[(1.0, 1.0), (4.0, 1.0), (1.0, 4.0)]);
+        assert!(square.minkowski_difference(&triangle).contains_origin());
+        assert!(triangle.minkowski_difference(&square).contains_origin());
+
+        let far = triangle.transform(&translation(5.0, 5.0));
+        assert!(!square.minkowski_difference(&far).contains_origin());
+        assert!(!far.minkowski_difference(&square).contains_origin());
+    }
+
+    #[test]
+    fn distance_to_touching_apart_and_overlapping() {
+        let square = poly(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+
+        let touching = square.transform(&translation(1.0, 0.0)).distance_to(&square);
+        assert_eq!(touching.to_bits(), 0.0f64.to_bits());
+
+        let apart = square.transform(&translation(4.0, 0.0));
+        assert!((apart.distance_to(&square) - 3.0).abs() < 1e-12);
+        assert!((square.distance_to(&apart) - 3.0).abs() < 1e-12);
+
+        let overlapping = square.transform(&translation(0.5, 0.5)).distance_to(&square);
+        assert_eq!(overlapping.to_bits(), 0.0f64.to_bits());
+    }
+
+    #[test]
+    fn convex_depth_of_concentric_rings() {
+        // ring k has radius 4 - k, so the outermost ring is layer 0, the rings are interleaved in the input
+        let mut points = vec![];
+        let mut expected = vec![];
+        for i in 0..12 {
+            for k in 0..3 {
+                let (sin, cos) = (i as f64 * f64::PI() / 6.0 + k as f64 * 0.1).sin_cos();
+                let r = 4.0 - k as f64;
+                points.push(Point::new(r * cos, r * sin));
+                expected.push(k);
+            }
+        }
+
+        assert_eq!(convex_depth(&points), expected);
+    }
+
+    #[test]
+    fn reflect_across_the_x_axis_stays_ccw_and_mirrors_containment() {
+        let triangle = poly(&[(1.0, 1.0), (5.0, 2.0), (2.0, 4.0)]);
+        let mirrored = triangle.reflect(Point::new(0.0, 0.0), Point::new(1.0, 0.0));
+
+        // the signed area is positive for a ccw hull
+        assert!((mirrored.area() - triangle.area()).abs() < 1e-12);
+        assert!(mirrored.area() > 0.0);
+
+        for p in probes(&triangle) {
+            let flipped = Point::new(p.x, -p.y);
+            for boundary in [Inclusion::Inclusive, Inclusion::Exclusive] {
+                assert_eq!(
+                    is_point_in_polygon(&triangle, &p, boundary),
+                    is_point_in_polygon(&mirrored, &flipped, boundary),
+                    "{p:?} {boundary:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bin_points_by_fan_triangle_counts_known_placements() {
+        let square = poly(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        let triangles = square.triangulate_fan();
+        assert_eq!(triangles.len(), 2);
+        let centroid = |[a, b, c]: [Point; 3]| Point::new((a.x + b.x + c.x) / 3.0, (a.y + b.y + c.y) / 3.0);
+        let (first, second) = (centroid(triangles[0]), centroid(triangles[1]));
+        let [apex, _, far] = triangles[0];
+
+        let points = [
+            first, first, first,
+            second,
+            // on the diagonal between both triangles, counted for the first one
+            apex + (far - apex) * 0.5,
+            // outside
+            Point::new(5.0, 5.0), Point::new(-1.0, 2.0),
+        ];
+        assert_eq!(square.bin_points_by_fan_triangle(&points), vec![4, 1]);
+        assert_eq!(square.bin_points_by_fan_triangle(&[]), vec![0, 0]);
+    }
+
+    #[test]
+    fn tangent_distance_from_beyond_a_corner_of_a_square() {
+        let square = poly(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+
+        // seen from diagonally beyond (2, 2) the tangents touch (2, 0) and (0, 2)
+        let d = square.tangent_distance(&Point::new(3.0, 3.0)).unwrap();
+        assert!((d - 10f64.sqrt()).abs() < 1e-12, "{}", d);
+
+        // seen from the right the tangents touch both corners of that side
+        let d = square.tangent_distance(&Point::new(3.0, 1.0)).unwrap();
+        assert!((d - 2f64.sqrt()).abs() < 1e-12, "{}", d);
+
+        assert_eq!(square.tangent_distance(&Point::new(2.0, 2.0)), None);
+        assert_eq!(square.tangent_distance(&Point::new(1.0, 1.0)), None);
+    }
+
+    #[test]
+    fn rasterized_rectangle_covers_its_area_within_one_row() {
+        let rect = poly(&[(1.2, 2.3), (7.6, 2.3), (7.6, 5.9), (1.2, 5.9)]);
+        let rotated = rect.transform(&(translation(5.0, 4.1) * rotation(0.3) * translation(-4.4, -4.1)));
+
+        for poly in [rect, rotated] {
+            let mask = poly.rasterize(12, 10);
+            assert_eq!(mask.len(), 120);
+
+            // the widest row of the rectangle bounds how far the cells can be off from the area
+            let bbox = poly.bounding_box();
+            let filled = mask.iter().filter(|&&cell| cell).count() as f64;
+            assert!((filled - poly.area()).abs() <= bbox.max.x - bbox.min.x, "{} cells for an area of {}", filled, poly.area());
+        }
+    }
+
+    #[test]
+    fn scanline_spans_of_a_triangle_shrink_toward_the_apex() {
+        let triangle = poly(&[(0.0, 0.0), (8.0, 0.0), (4.0, 8.0)]);
+        let ys: Vec<f64> = (0..8).map(|i| i as f64 + 0.5).collect();
+        let spans = triangle.scanline_spans(&ys);
+
+        for (&y, &(left, right)) in ys.iter().zip(&spans) {
+            assert!((right - left - (8.0 - y)).abs() < 1e-12, "{} at y = {}", right - left, y);
+            assert!((left + right - 8.0).abs() < 1e-12);
+        }
+        assert!(spans.windows(2).all(|w| w[1].1 - w[1].0 < w[0].1 - w[0].0));
+
+        // the apex is a span of width 0 and a line above it misses the triangle
+        assert_eq!(triangle.scanline_spans(&[8.0, 9.0]), vec![(4.0, 4.0), (f64::INFINITY, f64::NEG_INFINITY)]);
+    }
+
+    #[test]
+    fn contains_polygon_inside_overlapping_and_identical() {
+        let big = poly(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+        let small = poly(&[(2.0, 2.0), (4.0, 2.0), (4.0, 4.0), (2.0, 4.0)]);
+        assert!(big.contains_polygon(&small));
+        assert!(!small.contains_polygon(&big));
+
+        let overlapping = small.transform(&translation(7.0, 0.0));
+        assert!(!big.contains_polygon(&overlapping));
+
+        // the boundary counts as inside, so a polygon contains itself
+        assert!(big.contains_polygon(&big.clone()));
+    }
+}
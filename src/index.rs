@@ -0,0 +1,110 @@
+use crate::{Aabb, ConvexPoly, Inclusion, is_point_in_polygon, Point};
+
+/// Many hulls bucketed by their bounding boxes into a uniform grid, so a containment query only
+/// has to test the hulls whose box overlaps the cell of the point instead of all of them.
+#[derive(Debug, Clone)]
+pub struct HullIndex {
+    polys: Vec<ConvexPoly>,
+    // union of all bounding boxes, none if there are no hulls
+    bounds: Option<Aabb>,
+    columns: usize,
+    rows: usize,
+    // indices into `polys` for every cell, row by row
+    cells: Vec<Vec<usize>>,
+}
+
+impl HullIndex {
+    /// Builds a grid with about as many cells as there are hulls.
+    pub fn new(polys: Vec<ConvexPoly>) -> Self {
+        let bounds = Aabb::from_points(polys.iter().flat_map(|p| {
+            let bbox = p.bounding_box();
+            [bbox.min, bbox.max]
+        }));
+
+        let side = (polys.len() as f64).sqrt().ceil() as usize;
+        let mut index = HullIndex {
+            polys: vec![],
+            bounds,
+            columns: side,
+            rows: side,
+            cells: vec![vec![]; side * side],
+        };
+
+        for (i, poly) in polys.iter().enumerate() {
+            let bbox = poly.bounding_box();
+            let (c0, r0) = index.cell(&bbox.min);
+            let (c1, r1) = index.cell(&bbox.max);
+
+            for r in r0..=r1 {
+                for c in c0..=c1 {
+                    index.cells[r * index.columns + c].push(i);
+                }
+            }
+        }
+
+        index.polys = polys;
+        index
+    }
+
+    pub fn polys(&self) -> &[ConvexPoly] {
+        &self.polys
+    }
+
+    /// Index of the first hull which contains `p`, points on the boundary count as inside.
+    pub fn which_contains(&self, p: &Point) -> Option<usize> {
+        if !self.bounds?.contains(p) {
+            return None;
+        }
+
+        let (c, r) = self.cell(p);
+        self.cells[r * self.columns + c].iter()
+            .copied()
+            .find(|&i| is_point_in_polygon(&self.polys[i], p, Inclusion::Inclusive))
+    }
+
+    // column and row of the cell containing p, points outside are clamped to the nearest cell
+    fn cell(&self, p: &Point) -> (usize, usize) {
+        let Some(bounds) = self.bounds else {
+            return (0, 0);
+        };
+
+        let size = bounds.max - bounds.min;
+        // a zero sized grid divides by zero, the nan then saturates to the first cell
+        let c = ((p.x - bounds.min.x) / size.x * self.columns as f64) as usize;
+        let r = ((p.y - bounds.min.y) / size.y * self.rows as f64) as usize;
+
+        (c.min(self.columns - 1), r.min(self.rows - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, side: f64) -> ConvexPoly {
+        ConvexPoly::new(vec![
+            Point::new(x, y),
+            Point::new(x + side, y),
+            Point::new(x + side, y + side),
+            Point::new(x, y + side),
+        ]).unwrap()
+    }
+
+    #[test]
+    fn which_contains_on_a_grid_of_squares() {
+        // 3x3 squares of side 2 with gaps of 1 between them
+        let polys: Vec<_> = (0..9).map(|i| square(3.0 * (i % 3) as f64, 3.0 * (i / 3) as f64, 2.0)).collect();
+        let index = HullIndex::new(polys);
+
+        for i in 0..9 {
+            let center = Point::new(3.0 * (i % 3) as f64 + 1.0, 3.0 * (i / 3) as f64 + 1.0);
+            assert_eq!(index.which_contains(&center), Some(i));
+        }
+
+        assert_eq!(index.which_contains(&Point::new(5.0, 5.0)), Some(4));
+        assert_eq!(index.which_contains(&Point::new(2.5, 1.0)), None);
+        assert_eq!(index.which_contains(&Point::new(-1.0, 1.0)), None);
+        assert_eq!(index.which_contains(&Point::new(20.0, 20.0)), None);
+        assert_eq!(HullIndex::new(vec![]).which_contains(&Point::new(0.0, 0.0)), None);
+    }
+}
@@ -0,0 +1,114 @@
+use std::io::{self, Read, Write};
+
+use crate::{ConvexPoly, Point};
+
+// starts every file written by `write_points_bin`, the last byte is the version of the format
+const MAGIC: [u8; 4] = *b"PPT\x01";
+
+/// Writes the points in a compact binary format, the magic bytes and the number of points as a
+/// little endian u64 followed by x and y of every point as little endian f64.
+pub fn write_points_bin<W: Write>(mut w: W, points: &[Point]) -> io::Result<()> {
+    w.write_all(&MAGIC)?;
+    w.write_all(&(points.len() as u64).to_le_bytes())?;
+
+    for p in points {
+        w.write_all(&p.x.to_le_bytes())?;
+        w.write_all(&p.y.to_le_bytes())?;
+    }
+
+    w.flush()
+}
+
+/// Reads points written by [`write_points_bin`]. A file which doesn't start with the magic bytes
+/// fails with `InvalidData`, one which ends early with `UnexpectedEof`.
+pub fn read_points_bin<R: Read>(mut r: R) -> io::Result<Vec<Point>> {
+    let mut magic = [0; 4];
+    r.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "not a binary point file"));
+    }
+
+    let mut count = [0; 8];
+    r.read_exact(&mut count)?;
+    let count = u64::from_le_bytes(count);
+
+    // the count isn't trusted for the allocation, a corrupt header would reserve gigabytes
+    let mut points = Vec::with_capacity(count.min(1 << 16) as usize);
+    let mut buf = [0; 16];
+    for _ in 0..count {
+        r.read_exact(&mut buf)?;
+        let (x, y) = buf.split_at(8);
+        points.push(Point::new(
+            f64::from_le_bytes(x.try_into().unwrap()),
+            f64::from_le_bytes(y.try_into().unwrap()),
+        ));
+    }
+
+    Ok(points)
+}
+
+/// The hull vertices, the area and whether each query point is inside as one json object of the
+/// form `{"hull": [[x, y], ...], "area": a, "classifications": [true, false, ...]}`.
+pub fn hull_to_json(poly: &ConvexPoly, classifications: &[bool]) -> String {
+    // finite floats and bools are all there is, not worth pulling in a json library for
+    let hull: Vec<String> = poly.hull().iter().map(|p| format!("[{},{}]", p.x, p.y)).collect();
+    let classifications: Vec<String> = classifications.iter().map(|c| c.to_string()).collect();
+
+    format!(
+        "{{\"hull\":[{}],\"area\":{},\"classifications\":[{}]}}",
+        hull.join(","),
+        poly.area(),
+        classifications.join(","),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_points_round_trip() {
+        let points = vec![Point::new(0.0, -1.5), Point::new(1e300, 3.25), Point::new(-0.0, f64::MIN_POSITIVE)];
+        let mut bytes = vec![];
+        write_points_bin(&mut bytes, &points).unwrap();
+
+        assert_eq!(bytes.len(), 4 + 8 + 16 * points.len());
+        assert_eq!(read_points_bin(bytes.as_slice()).unwrap(), points);
+
+        let truncated = read_points_bin(&bytes[..bytes.len() - 3]).unwrap_err();
+        assert_eq!(truncated.kind(), io::ErrorKind::UnexpectedEof);
+
+        bytes[0] = b'X';
+        assert_eq!(read_points_bin(bytes.as_slice()).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    // the value of `key` in a flat json object, arrays are matched by their brackets
+    fn json_value<'a>(json: &'a str, key: &str) -> &'a str {
+        let rest = &json[json.find(&format!("\"{key}\":")).unwrap() + key.len() + 3..];
+        let mut depth = 0;
+        let end = rest.find(|c| {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
+            depth == 0 && (c == ']' || c == ',' || c == '}')
+        }).unwrap();
+
+        if rest.starts_with('[') { &rest[..=end] } else { &rest[..end] }
+    }
+
+    #[test]
+    fn json_lists_every_hull_vertex() {
+        let square = ConvexPoly::new(vec![
+            Point::new(0.0, 0.0), Point::new(2.0, 0.0), Point::new(2.0, 2.0), Point::new(0.0, 2.0), Point::new(1.0, 1.0),
+        ]).unwrap();
+        let json = hull_to_json(&square, &[true, false]);
+        let hull = json_value(&json, "hull");
+
+        assert_eq!(hull.matches('[').count() - 1, square.hull().len());
+        assert_eq!(hull, "[[0,0],[2,0],[2,2],[0,2]]");
+        assert_eq!(json_value(&json, "area"), "4");
+        assert_eq!(json_value(&json, "classifications"), "[true,false]");
+    }
+}
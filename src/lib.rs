@@ -0,0 +1,15 @@
+mod geometry;
+mod hull;
+mod index;
+mod io;
+mod polygon;
+mod render;
+mod sample;
+
+pub use crate::geometry::*;
+pub use crate::hull::*;
+pub use crate::index::*;
+pub use crate::io::*;
+pub use crate::polygon::*;
+pub use crate::render::*;
+pub use crate::sample::*;
@@ -0,0 +1,323 @@
+use std::error::Error;
+use std::fmt;
+
+use cgmath::InnerSpace;
+
+use crate::{clip_half_plane, closest_point_on_segment, ConvexPoly, orient2d, Orientation, Point, point_segment_distance, segment_intersects};
+use crate::Orientation::*;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PolygonError {
+    TooFewVertices,
+    SelfIntersecting,
+}
+
+impl fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolygonError::TooFewVertices => write!(f, "a polygon needs at least 3 vertices"),
+            PolygonError::SelfIntersecting => write!(f, "the polygon boundary intersects itself"),
+        }
+    }
+}
+
+impl Error for PolygonError {}
+
+/// A simple (possibly concave) polygon whose boundary runs through the vertices in the given order.
+#[derive(Debug, Clone)]
+pub struct SimplePolygon {
+    vertices: Vec<Point>,
+}
+
+impl SimplePolygon {
+    pub fn new(vertices: Vec<Point>) -> Result<Self, PolygonError> {
+        if vertices.len() < 3 {
+            return Err(PolygonError::TooFewVertices);
+        }
+
+        if has_self_intersection(&vertices) {
+            return Err(PolygonError::SelfIntersecting);
+        }
+
+        Ok(SimplePolygon { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// Winding number test, works for concave polygons and either orientation.
+    pub fn contains(&self, p: &Point) -> bool {
+        let n = self.vertices.len();
+        let mut winding = 0;
+
+        for i in 0..n {
+            let s = &self.vertices[i];
+            let e = &self.vertices[(i + 1) % n];
+
+            if s.y <= p.y {
+                // upward crossing with p left of the edge
+                if e.y > p.y && Orientation::calc(s, p, e) == Rightwards {
+                    winding += 1;
+                }
+            } else if e.y <= p.y && Orientation::calc(s, p, e) == Leftwards {
+                // downward crossing with p right of the edge
+                winding -= 1;
+            }
+        }
+
+        winding != 0
+    }
+
+    /// Signed area by the shoelace formula, positive if the vertices are ccw.
+    pub fn area(&self) -> f64 {
+        let n = self.vertices.len();
+        let twice_area: f64 = (0..n)
+            .map(|i| {
+                let s = &self.vertices[i];
+                let e = &self.vertices[(i + 1) % n];
+                s.x * e.y - e.x * s.y
+            })
+            .sum();
+
+        twice_area / 2.0
+    }
+}
+
+/// An open chain of segments through the points in order. Unlike the polygon types it doesn't
+/// enclose anything, there is no edge back from the last to the first point.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Polyline(pub Vec<Point>);
+
+impl Polyline {
+    pub fn length(&self) -> f64 {
+        self.0.windows(2).map(|w| (w[1] - w[0]).magnitude()).sum()
+    }
+
+    /// The point on the polyline which is the closest to `p`, `None` if it has no points.
+    pub fn closest_point(&self, p: &Point) -> Option<Point> {
+        let closest = |(a, b): (&Point, &Point)| closest_point_on_segment(p, a, b);
+
+        // a single point is a segment of zero length to itself
+        self.0.iter()
+            .zip(self.0.iter().skip(1).chain(self.0.last()))
+            .map(closest)
+            .min_by(|a, b| (a - p).magnitude2().total_cmp(&(b - p).magnitude2()))
+    }
+
+    /// Points every `spacing` along the polyline measured by arc length, starting at the first
+    /// point. The last point is only included if the length is a multiple of `spacing`, which has
+    /// to be positive.
+    pub fn resample(&self, spacing: f64) -> Vec<Point> {
+        assert!(spacing > 0.0, "the spacing has to be positive");
+        let Some(first) = self.0.first() else {
+            return vec![];
+        };
+
+        let mut samples = vec![*first];
+        // arc length at the start of the current segment and of the next sample
+        let mut start = 0.0;
+        let mut next = spacing;
+
+        for w in self.0.windows(2) {
+            let length = (w[1] - w[0]).magnitude();
+            while next <= start + length {
+                samples.push(w[0] + (w[1] - w[0]) * ((next - start) / length));
+                next = spacing * samples.len() as f64;
+            }
+            start += length;
+        }
+
+        samples
+    }
+}
+
+/// Checks every pair of edges in O(n²), adjacent edges may share their common vertex but must not
+/// fold back onto each other.
+pub fn has_self_intersection(polygon: &[Point]) -> bool {
+    let n = polygon.len();
+    let edge = |i: usize| (&polygon[i], &polygon[(i + 1) % n]);
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let (a1, a2) = edge(i);
+            let (b1, b2) = edge(j);
+
+            if j == i + 1 || (i == 0 && j == n - 1) {
+                // the shared vertex is fine, but collinear edges pointing back overlap
+                let (s, shared, e) = if j == i + 1 { (a1, a2, b2) } else { (b1, a1, a2) };
+                if Orientation::calc(s, shared, e) == Collinear && (s - shared).dot(e - shared) > 0.0 {
+                    return true;
+                }
+                continue;
+            }
+
+            if segment_intersects(a1, a2, b1, b2) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Simplifies an open polyline with the Douglas-Peucker algorithm. The endpoints are kept and
+/// a point in between is only dropped if the simplified line passes it at most `epsilon` apart.
+pub fn simplify_douglas_peucker(polyline: &[Point], epsilon: f64) -> Vec<Point> {
+    if polyline.len() < 3 {
+        return polyline.to_vec();
+    }
+
+    let first = &polyline[0];
+    let last = &polyline[polyline.len() - 1];
+
+    // the point the farthest away from the line between the endpoints
+    let (index, distance) = polyline[1..polyline.len() - 1].iter()
+        .map(|p| point_segment_distance(p, first, last))
+        .enumerate()
+        .fold((0, f64::NEG_INFINITY), |best, (i, d)| if d > best.1 { (i + 1, d) } else { best });
+
+    if distance <= epsilon {
+        return vec![*first, *last];
+    }
+
+    // both halves share the farthest point, it is only kept once
+    let mut simplified = simplify_douglas_peucker(&polyline[..=index], epsilon);
+    simplified.pop();
+    simplified.extend(simplify_douglas_peucker(&polyline[index..], epsilon));
+    simplified
+}
+
+/// The parts of the polygon `subject` which lie outside of `clip`. The outside of a convex polygon
+/// is split into one region per edge, the points beyond that edge but not beyond any earlier one,
+/// and `subject` is clipped to each region on its own. So even a piece which would be connected
+/// comes out split along those regions, e.g. a square with a hole in the middle gives 4 pieces.
+/// The pieces of a concave `subject` can contain edges of zero width along the region borders
+/// where they would fall apart. A degenerate `clip` has no area and leaves `subject` as it is.
+pub fn subtract_from(subject: &[Point], clip: &ConvexPoly) -> Vec<Vec<Point>> {
+    if clip.is_degenerate() {
+        return vec![subject.to_vec()];
+    }
+
+    let hull = clip.hull();
+    let n = hull.len();
+    let edges: Vec<(Point, Point)> = (0..n)
+        .map(|i| (hull[i], hull[(i + 1) % n]))
+        .filter(|(s, e)| s != e)
+        .collect();
+
+    let mut pieces = vec![];
+    for (i, (s, e)) in edges.iter().enumerate() {
+        let mut piece = clip_half_plane(subject, |q| -orient2d(s, e, q));
+        for (s, e) in &edges[..i] {
+            piece = clip_half_plane(&piece, |q| orient2d(s, e, q));
+        }
+
+        let twice_area: f64 = (0..piece.len())
+            .map(|k| piece[k].perp_dot(piece[(k + 1) % piece.len()]))
+            .sum();
+        if twice_area != 0.0 {
+            pieces.push(piece);
+        }
+    }
+
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Inclusion, is_point_in_polygon};
+
+    use super::*;
+
+    fn points(coords: &[(f64, f64)]) -> Vec<Point> {
+        coords.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    #[test]
+    fn concave_polygon_containment() {
+        // an L shape, the notch at the top right is outside
+        let l = SimplePolygon::new(points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (2.0, 2.0), (2.0, 4.0), (0.0, 4.0)])).unwrap();
+
+        assert!(l.contains(&Point::new(1.0, 1.0)));
+        assert!(l.contains(&Point::new(3.0, 1.0)));
+        assert!(l.contains(&Point::new(1.0, 3.0)));
+        assert!(!l.contains(&Point::new(3.0, 3.0)));
+        assert!(!l.contains(&Point::new(5.0, 1.0)));
+        assert_eq!(l.area(), 12.0);
+
+        // the same shape in cw order
+        let mut reversed = l.vertices().to_vec();
+        reversed.reverse();
+        let cw = SimplePolygon::new(reversed).unwrap();
+        assert!(cw.contains(&Point::new(1.0, 3.0)) && !cw.contains(&Point::new(3.0, 3.0)));
+        assert_eq!(cw.area(), -12.0);
+    }
+
+    #[test]
+    fn self_intersecting_polygon_is_rejected() {
+        let bowtie = points(&[(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)]);
+        assert_eq!(SimplePolygon::new(bowtie).unwrap_err(), PolygonError::SelfIntersecting);
+        assert_eq!(SimplePolygon::new(points(&[(0.0, 0.0), (1.0, 0.0)])).unwrap_err(), PolygonError::TooFewVertices);
+    }
+
+    #[test]
+    fn self_intersection_cases() {
+        assert!(!has_self_intersection(&points(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)])));
+        assert!(has_self_intersection(&points(&[(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)])));
+        // neighbouring edges share their endpoint, which is no intersection
+        assert!(!has_self_intersection(&points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)])));
+    }
+
+    #[test]
+    fn douglas_peucker_drops_or_keeps_the_corner() {
+        // an L shaped polyline with slight noise on both legs
+        let polyline = points(&[(0.0, 0.0), (2.0, 0.05), (4.0, 0.0), (4.05, 2.0), (4.0, 4.0)]);
+
+        assert_eq!(simplify_douglas_peucker(&polyline, 10.0), points(&[(0.0, 0.0), (4.0, 4.0)]));
+        assert_eq!(simplify_douglas_peucker(&polyline, 0.1), points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0)]));
+        assert_eq!(simplify_douglas_peucker(&polyline, 0.0), polyline);
+    }
+
+    #[test]
+    fn square_minus_inner_square() {
+        let subject = points(&[(0.0, 0.0), (6.0, 0.0), (6.0, 6.0), (0.0, 6.0)]);
+        let hole = ConvexPoly::new(points(&[(2.0, 2.0), (4.0, 2.0), (4.0, 4.0), (2.0, 4.0)])).unwrap();
+        let area = |piece: &[Point]| {
+            (0..piece.len()).map(|k| piece[k].perp_dot(piece[(k + 1) % piece.len()])).sum::<f64>() / 2.0
+        };
+
+        let pieces = subtract_from(&subject, &hole);
+        assert_eq!(pieces.len(), 4);
+        assert_eq!(pieces.iter().map(|piece| area(piece)).sum::<f64>(), 32.0);
+
+        // every piece lies outside of the hole, so its vertex average does too
+        for piece in &pieces {
+            let center = piece.iter().sum::<Point>() / piece.len() as f64;
+            assert!(!is_point_in_polygon(&hole, &center, Inclusion::Exclusive), "{piece:?}");
+        }
+
+        let far = ConvexPoly::new(points(&[(10.0, 10.0), (12.0, 10.0), (11.0, 12.0)])).unwrap();
+        let untouched = subtract_from(&subject, &far);
+        assert_eq!(untouched.len(), 1);
+        assert_eq!(area(&untouched[0]), 36.0);
+    }
+
+    #[test]
+    fn polyline_length_of_a_staircase() {
+        let stairs = Polyline(points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (3.0, 2.0)]));
+        assert_eq!(stairs.length(), 5.0);
+        assert_eq!(Polyline(points(&[(1.0, 1.0)])).length(), 0.0);
+        assert_eq!(Polyline::default().length(), 0.0);
+    }
+
+    #[test]
+    fn resample_a_straight_segment() {
+        let segment = Polyline(points(&[(0.0, 0.0), (10.0, 0.0)]));
+
+        // the end is included when the length is a multiple of the spacing
+        assert_eq!(segment.resample(2.5), points(&[(0.0, 0.0), (2.5, 0.0), (5.0, 0.0), (7.5, 0.0), (10.0, 0.0)]));
+        assert_eq!(segment.resample(3.0), points(&[(0.0, 0.0), (3.0, 0.0), (6.0, 0.0), (9.0, 0.0)]));
+        assert_eq!(segment.resample(20.0), points(&[(0.0, 0.0)]));
+    }
+}
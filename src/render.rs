@@ -0,0 +1,616 @@
+use svg::{Document, Node, node};
+use svg::node::element::{Animate, Circle, Group, Path, Rectangle, Text, Title};
+use svg::node::element::path::Data;
+
+use crate::{Aabb, ConvexPoly, Inclusion, is_point_in_polygon, Point};
+use crate::hull::graham_scan;
+
+pub const GREEN_FILL: &str = "#90ED90";
+pub const GREEN_STROKE: &str = "#006300";
+pub const RED_FILL: &str = "#ff0000";
+pub const RED_STROKE: &str = "#8A0000";
+
+pub const RED_OUTSIDE_FILL: &str = "#ff9999";
+pub const RED_OUTSIDE_STROKE: &str = "#ff5555";
+
+// how long each step of an animation is shown
+const ANIMATION_STEP_SECONDS: f64 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct TextStyle {
+    pub font_size: f64,
+    pub fill: String,
+    // outline around the glyphs, none draws the text without a stroke
+    pub stroke: Option<String>,
+    pub stroke_width: f64,
+    pub bold: bool,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            font_size: 18.0,
+            fill: "white".into(),
+            stroke: Some("black".into()),
+            stroke_width: 0.8,
+            bold: true,
+        }
+    }
+}
+
+impl TextStyle {
+    // plain black text as used for the caption and legend
+    fn plain(font_size: f64) -> Self {
+        TextStyle {
+            font_size,
+            fill: "black".into(),
+            stroke: None,
+            bold: false,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PointStyle {
+    pub stroke_width: f64,
+    // multiplies the radius passed to `add_point`, useful for canvases much larger or smaller than 500px
+    pub radius_scale: f64,
+}
+
+impl Default for PointStyle {
+    fn default() -> Self {
+        PointStyle {
+            stroke_width: 2.0,
+            radius_scale: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub width: f64,
+    pub height: f64,
+    // flips the y axis so y points up like in the usual math convention instead of down like in svg
+    pub flip_y: bool,
+    // draws the bounding box of every polygon as a dashed rectangle
+    pub draw_bbox: bool,
+    // text in the top left corner, followed by a legend of the point colors
+    pub caption: Option<String>,
+    // style of the labels drawn with `add_text`
+    pub text_style: TextStyle,
+    pub point_style: PointStyle,
+    // fill of the rectangle behind everything, none leaves the background transparent
+    pub background: Option<String>,
+    // dash pattern of the diagnostic overlays drawn with `overlay_stroke`, none draws them solid
+    pub overlay_dash: Option<String>,
+    pub overlay_width: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            width: 500.0,
+            height: 500.0,
+            flip_y: true,
+            draw_bbox: false,
+            caption: None,
+            text_style: TextStyle::default(),
+            point_style: PointStyle::default(),
+            background: Some("white".into()),
+            overlay_dash: Some("2 1".into()),
+            overlay_width: 1.0,
+        }
+    }
+}
+
+/// Maps `p` in `[0, 1]` to a gray from black to white, values outside are clamped.
+pub fn grayscale_hex(p: f64) -> String {
+    let s = ((p * 255.0) as i16).clamp(0, 255);
+    format!("#{:02x}{:02x}{:02x}", s, s, s)
+}
+
+/// Converts a color from hsv to `#rrggbb`, all three components are in `[0, 1]` and the hue wraps around.
+pub fn hsv_hex(h: f64, s: f64, v: f64) -> String {
+    let h = h.rem_euclid(1.0) * 6.0;
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let m = v - c;
+
+    let (r, g, b) = match h as u8 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+
+    let channel = |c: f64| (((c + m) * 255.0).round() as i16).clamp(0, 255);
+    format!("#{:02x}{:02x}{:02x}", channel(r), channel(g), channel(b))
+}
+
+/// `n` colors with evenly spaced hues, light enough to still see black strokes on them.
+pub fn palette(n: usize) -> Vec<String> {
+    (0..n)
+        .map(|i| hsv_hex(i as f64 / n as f64, 0.6, 0.95))
+        .collect()
+}
+
+pub fn add_point(doc: Document, p: &Point, opts: &RenderOptions, color: &str, radius: i32, stroke: &str) -> Document {
+    doc.add(circle(p, opts, color, stroke, radius))
+}
+
+/// Same as [`add_point`] but the point gets a `<title>`, which browsers show as a tooltip on hover.
+pub fn add_point_with_title(doc: Document, p: &Point, opts: &RenderOptions, color: &str, radius: i32, stroke: &str, title: String) -> Document {
+    doc.add(circle(p, opts, color, stroke, radius).add(Title::new().add(node::Text::new(title))))
+}
+
+// text at canvas coordinates
+fn text_node(x: f64, y: f64, text: String, style: &TextStyle) -> Text {
+    let mut t = Text::new()
+        .set("x", x)
+        .set("y", y)
+        .set("fill", style.fill.as_str())
+        .set("font-size", style.font_size);
+
+    if let Some(stroke) = &style.stroke {
+        t = t.set("stroke", stroke.as_str()).set("stroke-width", style.stroke_width);
+    }
+
+    if style.bold {
+        t = t.set("font-weight", "bold");
+    }
+
+    t.add(node::Text::new(text))
+}
+
+pub fn add_text(doc: Document, p: &Point, opts: &RenderOptions, text: String) -> Document {
+    let (x, y) = to_canvas(p, opts);
+    doc.add(text_node(x + 5.0, y - 5.0, text, &opts.text_style))
+}
+
+// the caption in the top left corner with a legend of the hull and point colors below it
+fn add_caption(mut doc: Document, caption: &str) -> Document {
+    doc = doc.add(text_node(10.0, 24.0, caption.into(), &TextStyle::plain(16.0)));
+
+    let legend = [
+        (GREEN_FILL, GREEN_STROKE, "hull"),
+        (RED_FILL, RED_STROKE, "inside"),
+        (RED_OUTSIDE_FILL, RED_OUTSIDE_STROKE, "outside"),
+    ];
+
+    for (i, (fill, stroke, label)) in legend.into_iter().enumerate() {
+        let y = 44.0 + i as f64 * 18.0;
+        let marker = Circle::new()
+            .set("cx", 16.0)
+            .set("cy", y)
+            .set("fill", fill)
+            .set("stroke", stroke)
+            .set("stroke-width", 2)
+            .set("r", 5);
+
+        doc = doc
+            .add(marker)
+            .add(text_node(28.0, y + 4.0, label.into(), &TextStyle::plain(12.0)));
+    }
+
+    doc
+}
+
+/// Strokes `node` like a diagnostic overlay, e.g. the lines of a triangulation or the marker of
+/// a search result, with the dash pattern and width from the options.
+pub fn overlay_stroke<T: Node>(mut node: T, stroke: &str, opts: &RenderOptions) -> T {
+    node.assign("stroke", stroke);
+    node.assign("stroke-width", opts.overlay_width);
+    if let Some(dash) = &opts.overlay_dash {
+        node.assign("stroke-dasharray", dash.as_str());
+    }
+    node
+}
+
+fn bbox_rectangle(bbox: &Aabb, opts: &RenderOptions, stroke: &str) -> Rectangle {
+    // the top left corner on the canvas depends on whether y is flipped
+    let (x, y0) = to_canvas(&bbox.min, opts);
+    let (_, y1) = to_canvas(&bbox.max, opts);
+
+    Rectangle::new()
+        .set("x", x)
+        .set("y", y0.min(y1))
+        .set("width", bbox.max.x - bbox.min.x)
+        .set("height", bbox.max.y - bbox.min.y)
+        .set("fill", "none")
+        .set("stroke", stroke)
+        .set("stroke-width", 1)
+        .set("stroke-dasharray", "4 2")
+}
+
+/// An empty document of the configured size with the background and the caption, if any.
+pub fn new_document(opts: &RenderOptions) -> Document {
+    let mut document = Document::new()
+        .set("viewBox", (0, 0, opts.width, opts.height))
+        .set("width", opts.width)
+        .set("height", opts.height);
+
+    if let Some(background) = &opts.background {
+        document = document.add(
+            Rectangle::new()
+                .set("fill", background.as_str())
+                .set("width", opts.width)
+                .set("height", opts.height)
+        );
+    }
+
+    match &opts.caption {
+        Some(caption) => add_caption(document, caption),
+        None => document,
+    }
+}
+
+/// Converts a point to svg canvas coordinates.
+pub fn to_canvas(p: &Point, opts: &RenderOptions) -> (f64, f64) {
+    if opts.flip_y {
+        (p.x, opts.height - p.y)
+    } else {
+        (p.x, p.y)
+    }
+}
+
+fn circle(p: &Point, opts: &RenderOptions, fill: &str, stroke: &str, radius: i32) -> Circle {
+    let (x, y) = to_canvas(p, opts);
+    Circle::new()
+        .set("cx", x)
+        .set("cy", y)
+        .set("fill", fill)
+        .set("stroke", stroke)
+        .set("stroke-width", opts.point_style.stroke_width)
+        .set("r", radius as f64 * opts.point_style.radius_scale)
+}
+
+/// Animates the graham scan, every state of the hull stack is shown for a short time one
+/// after the other and the final hull stays visible at the end. Each step is one group which
+/// gets made visible by a single `<animate>` keyframe. Without points the document stays empty.
+pub fn animate_hull_construction(points: &[Point], opts: &RenderOptions) -> Document {
+    let mut document = new_document(opts);
+    if points.is_empty() {
+        return document;
+    }
+
+    for p in points {
+        document = document.add(circle(p, opts, GREEN_FILL, GREEN_STROKE, 4));
+    }
+
+    let mut steps: Vec<Vec<Point>> = vec![];
+    graham_scan(&mut points.to_vec(), |stack| steps.push(stack.to_vec()));
+
+    for (i, stack) in steps.iter().enumerate() {
+        let last = i + 1 == steps.len();
+
+        let mut data = Data::new().move_to(to_canvas(&stack[0], opts));
+        for p in &stack[1..] {
+            data = data.line_to(to_canvas(p, opts));
+        }
+        if last {
+            data = data.close();
+        }
+
+        let mut group = Group::new()
+            .set("opacity", 0)
+            .add(
+                Path::new()
+                    .set("fill", "none")
+                    .set("stroke", GREEN_STROKE)
+                    .set("stroke-width", 2)
+                    .set("d", data)
+            );
+
+        for p in stack {
+            group = group.add(circle(p, opts, RED_FILL, RED_STROKE, 5));
+        }
+
+        // every step is visible for its own time slot, the final hull is frozen in place
+        let keyframe = Animate::new()
+            .set("attributeName", "opacity")
+            .set("values", "1")
+            .set("begin", format!("{}s", i as f64 * ANIMATION_STEP_SECONDS))
+            .set("dur", format!("{ANIMATION_STEP_SECONDS}s"))
+            .set("fill", if last { "freeze" } else { "remove" });
+
+        document = document.add(group.add(keyframe));
+    }
+
+    document
+}
+
+/// Draws the fan triangulation of the hull, every triangle gets filled with its own color.
+pub fn render_triangulation(poly: &ConvexPoly, opts: &RenderOptions) -> Document {
+    let mut document = new_document(opts);
+    let triangles = poly.triangulate_fan();
+    let colors = palette(triangles.len());
+
+    for (triangle, color) in triangles.iter().zip(&colors) {
+        let data = Data::new()
+            .move_to(to_canvas(&triangle[0], opts))
+            .line_to(to_canvas(&triangle[1], opts))
+            .line_to(to_canvas(&triangle[2], opts))
+            .close();
+
+        let path = Path::new()
+            .set("fill", color.as_str())
+            .set("stroke", GREEN_STROKE)
+            .set("stroke-width", 1)
+            .set("d", data);
+
+        document = document.add(path);
+    }
+
+    for p in poly.hull() {
+        document = document.add(circle(p, opts, GREEN_FILL, GREEN_STROKE, 5));
+    }
+
+    document
+}
+
+/// Draws every point shaded by its value with [`grayscale_hex`], the smallest value is black and
+/// the largest one white. If all values are the same every point is black.
+pub fn render_scalar_field(points: &[(Point, f64)], opts: &RenderOptions) -> Document {
+    let mut document = new_document(opts);
+
+    let min = points.iter().map(|(_, v)| *v).fold(f64::INFINITY, f64::min);
+    let max = points.iter().map(|(_, v)| *v).fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+
+    for (p, value) in points {
+        let shade = if range > 0.0 { (value - min) / range } else { 0.0 };
+        // a gray outline keeps the white points visible on the background
+        document = document.add(circle(p, opts, &grayscale_hex(shade), "gray", 4));
+    }
+
+    document
+}
+
+/// Draws all hulls into one document, each with its own color from [`palette`]. The fills are
+/// half transparent so overlapping hulls stay visible.
+pub fn render_polygons(polys: &[ConvexPoly], opts: &RenderOptions) -> Document {
+    let mut document = new_document(opts);
+
+    for (poly, color) in polys.iter().zip(palette(polys.len())) {
+        let start = &poly.hull()[0];
+        let mut data = Data::new().move_to(to_canvas(start, opts));
+        for p in &poly.hull()[1..] {
+            data = data.line_to(to_canvas(p, opts));
+        }
+        data = data.close();
+
+        let path = Path::new()
+            .set("fill", color.as_str())
+            .set("fill-opacity", 0.5)
+            .set("stroke", color.as_str())
+            .set("stroke-width", 2)
+            .set("d", data);
+
+        document = document.add(path);
+
+        if opts.draw_bbox {
+            document = document.add(bbox_rectangle(&poly.bounding_box(), opts, color.as_str()));
+        }
+    }
+
+    document
+}
+
+/// A standalone html page showing the hull with `test_points` colored by whether they are inside,
+/// the caption becomes the title of the page.
+pub fn render_polygon_html(poly: &ConvexPoly, test_points: &[Point], opts: &RenderOptions) -> String {
+    let mut document = render_polygons(std::slice::from_ref(poly), opts);
+
+    for p in test_points {
+        document = if is_point_in_polygon(poly, p, Inclusion::Inclusive) {
+            document.add(circle(p, opts, RED_FILL, RED_STROKE, 5))
+        } else {
+            document.add(circle(p, opts, RED_OUTSIDE_FILL, RED_OUTSIDE_STROKE, 4))
+        };
+    }
+
+    let title = opts.caption.as_deref().unwrap_or("convex hull")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n{document}\n</body>\n</html>\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[(f64, f64)]) -> Vec<Point> {
+        coords.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    #[test]
+    fn animation_has_one_keyframe_per_step() {
+        let opts = RenderOptions::default();
+        let input = points(&[(-1.0, -1.0), (1.0, -1.0), (0.0, 0.0), (1.0, 1.0), (-1.0, 1.0)]);
+
+        let mut steps = 0;
+        graham_scan(&mut input.clone(), |_| steps += 1);
+
+        let svg = animate_hull_construction(&input, &opts).to_string();
+        assert_eq!(svg.matches("<animate").count(), steps);
+        assert_eq!(svg.matches("fill=\"freeze\"").count(), 1);
+    }
+
+    #[test]
+    fn animation_without_points_is_empty() {
+        let svg = animate_hull_construction(&[], &RenderOptions::default()).to_string();
+        assert_eq!(svg.matches("<animate").count(), 0);
+        assert_eq!(svg, new_document(&RenderOptions::default()).to_string());
+    }
+
+    #[test]
+    fn triangulation_draws_one_path_per_triangle() {
+        let poly = ConvexPoly::new(points(&[(0.0, 0.0), (2.0, 0.0), (3.0, 1.0), (2.0, 2.0), (0.0, 2.0), (1.0, 1.0)])).unwrap();
+        let svg = render_triangulation(&poly, &RenderOptions::default()).to_string();
+        assert_eq!(svg.matches("<path").count(), poly.hull().len() - 2);
+    }
+
+    #[test]
+    fn hsv_primaries_and_distinct_palette() {
+        assert_eq!(hsv_hex(0.0, 1.0, 1.0), "#ff0000");
+        assert_eq!(hsv_hex(1.0 / 3.0, 1.0, 1.0), "#00ff00");
+        assert_eq!(hsv_hex(2.0 / 3.0, 1.0, 1.0), "#0000ff");
+
+        let colors = palette(3);
+        assert_eq!(colors.len(), 3);
+        assert_ne!(colors[0], colors[1]);
+        assert_ne!(colors[1], colors[2]);
+        assert_ne!(colors[0], colors[2]);
+    }
+
+    // value of `name` in the first tag of `element`
+    fn attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+        let tag = &element[..element.find('>')?];
+        let start = tag.find(&format!(" {name}=\""))? + name.len() + 3;
+        Some(&tag[start..start + tag[start..].find('"')?])
+    }
+
+    #[test]
+    fn every_polygon_gets_its_own_fill() {
+        let polys: Vec<_> = (0..3)
+            .map(|i| {
+                let dx = 3.0 * i as f64;
+                ConvexPoly::new(points(&[(dx, 0.0), (dx + 2.0, 0.0), (dx + 1.0, 2.0)])).unwrap()
+            })
+            .collect();
+
+        let svg = render_polygons(&polys, &RenderOptions::default()).to_string();
+        let fills: Vec<_> = svg.split("<path").skip(1).map(|e| attribute(e, "fill").unwrap()).collect();
+
+        assert_eq!(fills.len(), 3);
+        assert_ne!(fills[0], fills[1]);
+        assert_ne!(fills[1], fills[2]);
+        assert_ne!(fills[0], fills[2]);
+    }
+
+    #[test]
+    fn to_canvas_with_and_without_flip() {
+        let p = Point::new(10.0, 30.0);
+        let mut opts = RenderOptions { height: 200.0, ..Default::default() };
+
+        opts.flip_y = true;
+        assert_eq!(to_canvas(&p, &opts), (10.0, 170.0));
+        opts.flip_y = false;
+        assert_eq!(to_canvas(&p, &opts), (10.0, 30.0));
+    }
+
+    #[test]
+    fn bbox_rectangle_matches_the_hull_bounds() {
+        let poly = ConvexPoly::new(points(&[(10.0, 20.0), (70.0, 30.0), (40.0, 60.0)])).unwrap();
+        let opts = RenderOptions { draw_bbox: true, background: None, ..Default::default() };
+
+        let svg = render_polygons(&[poly], &opts).to_string();
+        let rects: Vec<_> = svg.split("<rect").skip(1).collect();
+
+        assert_eq!(rects.len(), 1);
+        assert_eq!(attribute(rects[0], "x"), Some("10"));
+        assert_eq!(attribute(rects[0], "y"), Some("440"));
+        assert_eq!(attribute(rects[0], "width"), Some("60"));
+        assert_eq!(attribute(rects[0], "height"), Some("40"));
+    }
+
+    // text inside of all `<name>` elements
+    fn contents(svg: &str, name: &str) -> Vec<String> {
+        svg.split(&format!("<{name}")).skip(1)
+            .map(|e| {
+                let e = &e[e.find('>').unwrap() + 1..];
+                e[..e.find(&format!("</{name}>")).unwrap()].trim().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn caption_is_a_text_node() {
+        let opts = RenderOptions { caption: Some("hull of 5 points".into()), ..Default::default() };
+        let svg = new_document(&opts).to_string();
+        assert!(contents(&svg, "text").contains(&"hull of 5 points".to_string()));
+
+        let svg = new_document(&RenderOptions::default()).to_string();
+        assert!(contents(&svg, "text").is_empty());
+    }
+
+    #[test]
+    fn add_text_uses_the_configured_font_size() {
+        let mut opts = RenderOptions { background: None, ..Default::default() };
+        opts.text_style.font_size = 31.5;
+
+        let svg = add_text(new_document(&opts), &Point::new(1.0, 2.0), &opts, "a".into()).to_string();
+        let texts: Vec<_> = svg.split("<text").skip(1).collect();
+
+        assert_eq!(texts.len(), 1);
+        assert_eq!(attribute(texts[0], "font-size"), Some("31.5"));
+    }
+
+    #[test]
+    fn scalar_field_spans_black_to_white() {
+        let field = [(Point::new(1.0, 1.0), -2.0), (Point::new(2.0, 2.0), 0.5), (Point::new(3.0, 3.0), 7.0)];
+        let svg = render_scalar_field(&field, &RenderOptions { background: None, ..Default::default() }).to_string();
+        let fills: Vec<_> = svg.split("<circle").skip(1).map(|e| attribute(e, "fill").unwrap()).collect();
+
+        assert_eq!(fills.len(), 3);
+        assert_eq!(fills[0], "#000000");
+        assert_eq!(fills[2], "#ffffff");
+        assert!(fills[1] != "#000000" && fills[1] != "#ffffff");
+    }
+
+    #[test]
+    fn html_page_embeds_the_hull() {
+        let square = ConvexPoly::new(points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)])).unwrap();
+        let opts = RenderOptions { caption: Some("a <square>".into()), ..Default::default() };
+        let html = render_polygon_html(&square, &[Point::new(1.0, 1.0)], &opts);
+
+        assert!(html.contains("<html"));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("d=\"M0,500 L4,500 L4,496 L0,496 z\""));
+        assert!(html.contains("<title>a &lt;square&gt;</title>"));
+    }
+
+    #[test]
+    fn point_title_shows_the_coordinates() {
+        let opts = RenderOptions::default();
+        let p = Point::new(1.5, 2.0);
+        let svg = add_point_with_title(new_document(&opts), &p, &opts, RED_FILL, 5, RED_STROKE, format!("({}, {}) inside", p.x, p.y)).to_string();
+
+        // the title is a child of the circle so hovering the point shows it
+        let circle = &svg[svg.find("<circle").unwrap()..svg.find("</circle>").unwrap()];
+        assert_eq!(contents(circle, "title"), vec!["(1.5, 2) inside"]);
+    }
+
+    #[test]
+    fn background_none_leaves_out_the_canvas_rectangle() {
+        let opts = RenderOptions { background: Some("#123456".into()), ..Default::default() };
+        let svg = new_document(&opts).to_string();
+        let rects: Vec<_> = svg.split("<rect").skip(1).collect();
+        assert_eq!(rects.len(), 1);
+        assert_eq!(attribute(rects[0], "fill"), Some("#123456"));
+        assert_eq!(attribute(rects[0], "width"), Some(opts.width.to_string().as_str()));
+
+        let svg = new_document(&RenderOptions { background: None, ..Default::default() }).to_string();
+        assert!(!svg.contains("<rect"));
+    }
+
+    #[test]
+    fn overlay_dash_none_draws_solid_strokes() {
+        let opts = RenderOptions { overlay_dash: Some("5 3".into()), overlay_width: 2.5, ..Default::default() };
+        let dashed = overlay_stroke(Path::new(), "red", &opts).to_string();
+        assert_eq!(attribute(&dashed, "stroke-dasharray"), Some("5 3"));
+        assert_eq!(attribute(&dashed, "stroke-width"), Some("2.5"));
+
+        let opts = RenderOptions { overlay_dash: None, ..opts };
+        let solid = overlay_stroke(Path::new(), "red", &opts).to_string();
+        assert_eq!(attribute(&solid, "stroke-dasharray"), None);
+        assert_eq!(attribute(&solid, "stroke"), Some("red"));
+    }
+}
@@ -0,0 +1,33 @@
+use rand::distributions::Distribution;
+use rand::Rng;
+
+use crate::Point;
+
+/// Samples `n` points whose x and y coordinates are drawn independently from `dist_x` and `dist_y`.
+pub fn sample_points<R: Rng, D: Distribution<f64>>(rng: &mut R, dist_x: &D, dist_y: &D, n: usize) -> Vec<Point> {
+    (0..n)
+        .map(|_| Point::new(dist_x.sample(rng), dist_y.sample(rng)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::distributions::Uniform;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_points() {
+        let dist = Uniform::new(0.0, 100.0);
+        let first = sample_points(&mut StdRng::seed_from_u64(307), &dist, &dist, 50);
+        let second = sample_points(&mut StdRng::seed_from_u64(307), &dist, &dist, 50);
+        let other = sample_points(&mut StdRng::seed_from_u64(308), &dist, &dist, 50);
+
+        assert_eq!(first.len(), 50);
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert!(first.iter().all(|p| (0.0..100.0).contains(&p.x) && (0.0..100.0).contains(&p.y)));
+    }
+}
@@ -0,0 +1,64 @@
+use std::process::Command;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::Normal;
+
+use points_polygons_triangles_rs::*;
+
+// the value of `key` in the flat json object printed by `--format json`, arrays end at their
+// matching bracket
+fn json_value<'a>(json: &'a str, key: &str) -> &'a str {
+    let rest = &json[json.find(&format!("\"{key}\":")).unwrap() + key.len() + 3..];
+    let mut depth = 0;
+    let end = rest.find(|c| {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ => {}
+        }
+        depth == 0 && (c == ']' || c == ',' || c == '}')
+    }).unwrap();
+
+    if rest.starts_with('[') { &rest[..=end] } else { &rest[..end] }
+}
+
+// every number in a possibly nested json array of numbers, in order
+fn numbers(array: &str) -> Vec<f64> {
+    array.split(['[', ']', ','])
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn json_output_lists_the_hull_of_the_seeded_points() {
+    let seed = 381;
+    let output = Command::new(env!("CARGO_BIN_EXE_points-polygons-triangles-rs"))
+        .args(["--format", "json", "--seed", &seed.to_string()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let json = stdout.trim();
+    assert!(json.starts_with('{') && json.ends_with('}'), "not a json object: {json}");
+
+    // the same points as the demo draws, the green ones make up the hull
+    let mut rng = StdRng::seed_from_u64(seed);
+    let green_dist = Normal::new(250.0, 500.0 / 7.0).unwrap();
+    let red_dist = Normal::new(250.0, 500.0 / 6.0).unwrap();
+    let green = sample_points(&mut rng, &green_dist, &green_dist, 37);
+    let red = sample_points(&mut rng, &red_dist, &red_dist, 37);
+    let expected = ConvexPoly::new(green).unwrap();
+
+    let hull: Vec<Point> = numbers(json_value(json, "hull"))
+        .chunks(2)
+        .map(|c| Point::new(c[0], c[1]))
+        .collect();
+    assert_eq!(hull.len(), expected.hull().len());
+    assert_eq!(hull, expected.hull());
+
+    let classifications = json_value(json, "classifications");
+    assert_eq!(classifications.split(',').count(), red.len());
+}